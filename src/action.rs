@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{error::deserialize, Action, BookCore, BookCoreError};
+
+/// 书源要求先登录，`form` 为 `forms` 中登录表单的 id
+///
+/// 动作、书架、购买等入口都以 `{ type: 'loginRequired', form, message }` 返回
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LoginRequired {
+    pub form: Option<String>,
+    pub message: Option<String>,
+}
+
+impl LoginRequired {
+    /// 入口的返回值为 `{ type: 'loginRequired' }` 时解析出登录要求
+    pub(crate) fn from_value(value: &Value) -> Result<Option<Self>, BookCoreError> {
+        if value.get("type").and_then(Value::as_str) != Some("loginRequired") {
+            return Ok(None);
+        }
+        deserialize(value.clone()).map(Some)
+    }
+}
+
+/// 动作执行的结果，宿主按类型统一处理
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type")]
+pub enum ActionResult {
+    /// 没有需要展示的结果
+    #[serde(rename = "done")]
+    Done,
+    /// 提示信息
+    #[serde(rename = "message")]
+    Message { message: String },
+    /// 需要写入的 envs，返回前已写入 BookCore
+    #[serde(rename = "envs")]
+    EnvUpdate {
+        envs: Map<String, Value>,
+        message: Option<String>,
+    },
+    /// 请求宿主刷新书架
+    #[serde(rename = "refreshShelf")]
+    RefreshShelf { message: Option<String> },
+    /// 请求宿主打开链接
+    #[serde(rename = "openUrl")]
+    OpenUrl { url: String },
+    #[serde(rename = "loginRequired")]
+    LoginRequired(LoginRequired),
+    #[serde(rename = "error")]
+    Error { message: String },
+    /// 输入未通过 `Action.form` 的校验，以字段名为键
+    #[serde(skip)]
+    InvalidInput(BTreeMap<String, String>),
+}
+
+impl ActionResult {
+    /// 兼容直接返回字符串或空值的旧动作
+    fn from_value(value: Value) -> Result<Self, BookCoreError> {
+        match value {
+            Value::Object(_) => deserialize(value),
+            Value::Null | Value::Bool(true) => Ok(Self::Done),
+            Value::String(message) => Ok(Self::Message { message }),
+            value => Ok(Self::Message {
+                message: value.to_string(),
+            }),
+        }
+    }
+}
+
+impl BookCore {
+    /// 执行 `actions` 中声明的动作
+    ///
+    /// 声明了 `form` 的动作会先按表单补全并校验 `values`，再以取值为参数调用入口；
+    /// 没有表单的动作不传参数。返回 `envs` 类型时会先写入 envs
+    pub fn perform_action(
+        &mut self,
+        action: &str,
+        values: Map<String, Value>,
+    ) -> Result<ActionResult, BookCoreError> {
+        let actions: Vec<Action> = self.get_actions()?;
+        let Some(action) = actions.into_iter().find(|item| item.action == action) else {
+            return Err(BookCoreError::MissingEntry(action.to_string()));
+        };
+        let args = match &action.form {
+            Some(form) => {
+                let values = form.normalize(&values);
+                let errors = form.validate(&values);
+                if !errors.is_empty() {
+                    return Ok(ActionResult::InvalidInput(errors));
+                }
+                Value::Object(values)
+            }
+            None => Value::Null,
+        };
+        let value = self.invoke(&action.action, args)?;
+        self.action_result(value)
+    }
+
+    /// 把入口的返回值转换为 `ActionResult`，`envs` 类型会先写入 envs
+    pub(crate) fn action_result(&mut self, value: Value) -> Result<ActionResult, BookCoreError> {
+        let result = ActionResult::from_value(value)?;
+        if let ActionResult::EnvUpdate { envs, .. } = &result {
+            for (key, value) in envs {
+                self.set_env(key.clone(), value.clone())?;
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use boa_engine::{Context, Script, Source};
+use boa_runtime::Logger;
+use serde_json::json;
+use tokio::runtime::Handle;
+
+use crate::{
+    catalog::DEFAULT_MAX_LIST_PAGES,
+    chapter::DEFAULT_MAX_CHAPTER_PAGES,
+    request::scope::{RequestDefaults, RequestScope},
+    runtime::{init_runtime, Globals},
+    BookCore, BookCoreError, ExecutionLimits, Proxy,
+};
+
+type Setup = Box<dyn FnOnce(&mut BookCore) -> Result<(), BookCoreError>>;
+
+/// BookCore 的构建器，未设置的项与 `BookCore::init` 保持一致
+#[derive(Default)]
+pub struct BookCoreBuilder {
+    globals: Globals,
+    defaults: RequestDefaults,
+    runtime: Option<Handle>,
+    limits: ExecutionLimits,
+    logger: Option<Setup>,
+    max_chapter_pages: Option<usize>,
+    max_list_pages: Option<usize>,
+}
+
+impl BookCoreBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// JReqwest 的默认超时，脚本在 options 中指定 timeout 时以脚本为准
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.defaults.timeout = timeout;
+        self
+    }
+
+    /// 是否接受无效的 TLS 证书，默认接受
+    pub fn accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.defaults.accept_invalid_certs = accept;
+        self
+    }
+
+    /// 默认的 User-Agent，脚本在 headers 中指定时以脚本为准
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.defaults.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.defaults.proxy = Some(proxy);
+        self
+    }
+
+    /// 使用自定义的 Logger 替换默认的 console 输出
+    pub fn logger(mut self, logger: impl Logger + 'static) -> Self {
+        self.logger = Some(Box::new(move |core| core.regist_cust_logger(logger)));
+        self
+    }
+
+    pub fn globals(mut self, globals: Globals) -> Self {
+        self.globals = globals;
+        self
+    }
+
+    /// 见 `BookCore::set_runtime`
+    pub fn runtime(mut self, handle: Handle) -> Self {
+        self.runtime = Some(handle);
+        self
+    }
+
+    /// 默认的执行预算，同样作用于书源脚本的顶层代码
+    pub fn limits(mut self, limits: ExecutionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// 见 `BookCore::set_max_chapter_pages`
+    pub fn max_chapter_pages(mut self, max_pages: usize) -> Self {
+        self.max_chapter_pages = Some(max_pages.max(1));
+        self
+    }
+
+    /// 见 `BookCore::set_max_list_pages`
+    pub fn max_list_pages(mut self, max_pages: usize) -> Self {
+        self.max_list_pages = Some(max_pages.max(1));
+        self
+    }
+
+    pub fn build(self, code: String) -> Result<BookCore, BookCoreError> {
+        let mut core = BookCore {
+            context: Context::default(),
+            limits: self.limits,
+            cancel: None,
+            running: false,
+            max_chapter_pages: self.max_chapter_pages.unwrap_or(DEFAULT_MAX_CHAPTER_PAGES),
+            max_list_pages: self.max_list_pages.unwrap_or(DEFAULT_MAX_LIST_PAGES),
+        };
+        init_runtime(&mut core.context, &self.globals);
+        RequestScope {
+            defaults: self.defaults,
+            ..RequestScope::default()
+        }
+        .install(&core.context);
+        if let Some(handle) = self.runtime {
+            core.set_runtime(handle);
+        }
+        if let Some(logger) = self.logger {
+            logger(&mut core)?;
+        }
+        core.set_envs(json!({}))?;
+        let script = Script::parse(Source::from_bytes(code.as_str()), None, &mut core.context)
+            .map_err(BookCoreError::syntax)?;
+        core.run(|core| {
+            script
+                .evaluate(&mut core.context)
+                .map_err(|err| core.js_error(err))?;
+            core.context.run_jobs();
+            Ok(())
+        })?;
+        Ok(core)
+    }
+}
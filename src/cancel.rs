@@ -0,0 +1,29 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// 取消正在执行的调用
+///
+/// 可以克隆后交给其他线程，`cancel` 之后 JReqwest 会丢弃未完成的请求，
+/// 脚本在下一个检查点停止，调用返回 `BookCoreError::Cancelled`。
+/// 检查点只在 JReqwest、JScraper、xml2Json 等原生函数中，不调用它们的纯 JS 循环无法被取消，
+/// 只能在设置了超时或循环次数上限时停止
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
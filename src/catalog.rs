@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::deserialize, BookCore, BookCoreError, CatalogVolume};
+
+/// 目录、书架等分页列表默认最多跟随的页数
+pub(crate) const DEFAULT_MAX_LIST_PAGES: usize = 200;
+
+/// 传给书源 `catalog` 的参数，`page` 从 1 开始，`continuation` 为上一页返回的令牌
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CatalogQuery {
+    pub bid: String,
+    pub page: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<Value>,
+}
+
+impl CatalogQuery {
+    pub fn new(bid: impl Into<String>) -> Self {
+        CatalogQuery {
+            bid: bid.into(),
+            page: 1,
+            continuation: None,
+        }
+    }
+
+    /// 下一页的查询，本页没有 continuation 时为空
+    pub fn next(&self, page: &CatalogPage) -> Option<Self> {
+        page.continuation.clone().map(|continuation| CatalogQuery {
+            bid: self.bid.clone(),
+            page: self.page + 1,
+            continuation: Some(continuation),
+        })
+    }
+}
+
+/// 一页目录，同一卷可能被拆分在相邻的几页中
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CatalogPage {
+    pub volumes: Vec<CatalogVolume>,
+    pub continuation: Option<Value>,
+}
+
+impl CatalogPage {
+    pub fn has_more(&self) -> bool {
+        self.continuation.is_some()
+    }
+}
+
+/// 逐页获取目录的迭代器，由 `BookCore::catalog_pages` 创建
+///
+/// 出错后迭代结束；书源返回已经出现过的 continuation 时视为循环并停止，
+/// 超过 `max_list_pages` 页时返回 `BookCoreError::TooManyPages`
+pub struct CatalogPages<'a> {
+    core: &'a mut BookCore,
+    query: Option<CatalogQuery>,
+    paging: Paging,
+}
+
+impl Iterator for CatalogPages<'_> {
+    type Item = Result<CatalogPage, BookCoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let query = self.query.take()?;
+        if let Err(err) = self.core.start_page(&self.paging, query.page) {
+            return Some(Err(err));
+        }
+        let page = match self.core.get_catalog_page(query.clone()) {
+            Ok(page) => page,
+            Err(err) => return Some(Err(err)),
+        };
+        self.query = next_query(&query, &page, &mut self.paging);
+        Some(Ok(page))
+    }
+}
+
+/// 目录、书架等分页列表的翻页状态：限制页数，并记录出现过的 continuation 以识别循环
+pub(crate) struct Paging {
+    max_pages: usize,
+    seen: HashSet<String>,
+}
+
+impl Paging {
+    pub(crate) fn new(max_pages: usize) -> Self {
+        Paging {
+            max_pages,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// 获取第 `page` 页之前调用，超过上限时返回 `TooManyPages`
+    pub(crate) fn check(&self, page: u32) -> Result<(), BookCoreError> {
+        if page as usize > self.max_pages {
+            return Err(BookCoreError::TooManyPages(self.max_pages));
+        }
+        Ok(())
+    }
+
+    /// 记录下一页的 continuation，已经出现过时返回 false
+    pub(crate) fn follow(&mut self, continuation: &Value) -> bool {
+        self.seen.insert(continuation.to_string())
+    }
+}
+
+impl BookCore {
+    /// 在同一次执行中获取第 `page` 页之前调用：检查页数上限，翻页时检查截止时间与取消
+    pub(crate) fn start_page(&self, paging: &Paging, page: u32) -> Result<(), BookCoreError> {
+        paging.check(page)?;
+        if page > 1 {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+}
+
+/// 下一页的查询，continuation 重复时为空
+pub(crate) fn next_query(
+    query: &CatalogQuery,
+    page: &CatalogPage,
+    paging: &mut Paging,
+) -> Option<CatalogQuery> {
+    let continuation = page.continuation.as_ref()?;
+    if !paging.follow(continuation) {
+        return None;
+    }
+    query.next(page)
+}
+
+/// 把一页目录合并进已有的卷
+///
+/// id 相同的卷视为同一卷，章节追加在其后，卷内重复 id 的章节只保留第一次出现的
+pub fn merge_volumes(volumes: &mut Vec<CatalogVolume>, incoming: Vec<CatalogVolume>) {
+    for volume in incoming {
+        match volumes.iter_mut().find(|current| current.id == volume.id) {
+            Some(current) => {
+                let mut ids: HashSet<String> = current
+                    .chapters
+                    .iter()
+                    .map(|chapter| chapter.id.clone())
+                    .collect();
+                current.chapters.extend(
+                    volume
+                        .chapters
+                        .into_iter()
+                        .filter(|chapter| ids.insert(chapter.id.clone())),
+                );
+            }
+            None => volumes.push(volume),
+        }
+    }
+}
+
+impl BookCore {
+    /// 设置目录、书架等分页列表最多跟随的页数，超过时返回 `BookCoreError::TooManyPages`
+    pub fn set_max_list_pages(&mut self, max_pages: usize) {
+        self.max_list_pages = max_pages.max(1);
+    }
+
+    /// 获取一页目录
+    ///
+    /// 书源的 `catalog` 可以返回卷的数组，视为只有一页；也可以返回 `{ volumes, continuation }`，
+    /// continuation 不为空时以它请求下一页
+    pub fn get_catalog_page(&mut self, query: CatalogQuery) -> Result<CatalogPage, BookCoreError> {
+        let args = serde_json::to_value(&query).expect("CatalogQuery is always serializable");
+        let value: Value = self.invoke("catalog", args)?;
+        if value.is_array() {
+            return Ok(CatalogPage {
+                volumes: deserialize(value)?,
+                continuation: None,
+            });
+        }
+        deserialize(value)
+    }
+
+    /// 逐页获取目录，适合边加载边展示章节很多的书
+    pub fn catalog_pages(&mut self, bid: impl Into<String>) -> CatalogPages<'_> {
+        let paging = Paging::new(self.max_list_pages);
+        CatalogPages {
+            core: self,
+            query: Some(CatalogQuery::new(bid)),
+            paging,
+        }
+    }
+
+    /// 获取全部目录，跨页拆分的卷按 id 合并，所有分页在同一次执行预算内完成
+    pub fn get_catalog(&mut self, bid: String) -> Result<Vec<CatalogVolume>, BookCoreError> {
+        self.run(|core| {
+            let mut volumes = Vec::new();
+            for page in core.catalog_pages(bid) {
+                merge_volumes(&mut volumes, page?.volumes);
+            }
+            Ok(volumes)
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+
+use serde_json::json;
+
+use crate::{BookCore, BookCoreError, Chapter, ContentBlock};
+
+/// 单个章节默认最多跟随的分页数
+pub(crate) const DEFAULT_MAX_CHAPTER_PAGES: usize = 50;
+
+impl BookCore {
+    /// 设置 `get_chapter` 最多跟随的分页数，超过时返回 `BookCoreError::TooManyPages`
+    pub fn set_max_chapter_pages(&mut self, max_pages: usize) {
+        self.max_chapter_pages = max_pages.max(1);
+    }
+
+    /// 获取章节并拼接分页
+    ///
+    /// 书源的 `chapter` 返回 `continuation` 时，继续以 `{ bid, cid, continuation, page }` 调用，
+    /// 直到不再返回 continuation。重复出现的 continuation 或与上一页完全相同的内容视为循环并停止，
+    /// 相邻两页首尾重复的块只保留一份；纯文本至少重复两行才去重，避免误删“……”这类正常重复的短行
+    pub(crate) fn fetch_chapter(&mut self, bid: &str, cid: &str) -> Result<Chapter, BookCoreError> {
+        let mut chapter: Chapter = self.invoke("chapter", json!({ "bid": bid, "cid": cid }))?;
+        let mut seen = HashSet::new();
+        let mut previous = (chapter.content.clone(), chapter.blocks.clone());
+        let mut page = 1;
+        while let Some(continuation) = chapter.continuation.take() {
+            if !seen.insert(continuation.to_string()) {
+                break;
+            }
+            if page >= self.max_chapter_pages {
+                return Err(BookCoreError::TooManyPages(self.max_chapter_pages));
+            }
+            // 页与页之间没有原生检查点，在这里检查整章共用的截止时间与取消
+            self.checkpoint()?;
+            page += 1;
+            let mut next: Chapter = self.invoke(
+                "chapter",
+                json!({ "bid": bid, "cid": cid, "continuation": continuation, "page": page }),
+            )?;
+            if (&next.content, &next.blocks) == (&previous.0, &previous.1) {
+                break;
+            }
+            previous = (next.content.clone(), next.blocks.clone());
+            chapter.continuation = next.continuation.take();
+            chapter.append(next);
+        }
+        Ok(chapter)
+    }
+}
+
+impl Chapter {
+    /// 把下一页拼接到当前章节
+    fn append(&mut self, next: Chapter) {
+        if !next.content.is_empty() {
+            let current: Vec<&str> = self.content.lines().collect();
+            let incoming: Vec<&str> = next.content.lines().collect();
+            let overlap = overlap(&current, &incoming);
+            let rest = incoming[overlap..].join("\n");
+            if self.content.is_empty() {
+                self.content = rest;
+            } else if !rest.is_empty() {
+                self.content.push('\n');
+                self.content.push_str(&rest);
+            }
+        }
+        if let Some(incoming) = next.blocks {
+            let blocks = self.blocks.get_or_insert_with(Vec::new);
+            let overlap = overlap(blocks, &incoming);
+            blocks.extend(incoming.into_iter().skip(overlap));
+        }
+        self.word_count = match (self.word_count, next.word_count) {
+            (Some(current), Some(incoming)) => Some(current + incoming),
+            (current, incoming) => current.or(incoming),
+        };
+        self.comment_count = match (self.comment_count, next.comment_count) {
+            (Some(current), Some(incoming)) => Some(current + incoming),
+            (current, incoming) => current.or(incoming),
+        };
+    }
+}
+
+/// 至少重复多少个有效项才视为分页重叠，只重复一项（例如单独的“……”）时多半是巧合
+const MIN_OVERLAP: usize = 2;
+
+/// `current` 的结尾与 `incoming` 的开头重复的最大长度，有效项少于 `MIN_OVERLAP` 的重复不计入
+fn overlap<T: Overlap>(current: &[T], incoming: &[T]) -> usize {
+    let max = current.len().min(incoming.len());
+    (1..=max)
+        .rev()
+        .find(|&len| {
+            let tail = &current[current.len() - len..];
+            let head = &incoming[..len];
+            tail.iter().filter(|item| item.is_significant()).count() >= MIN_OVERLAP && tail == head
+        })
+        .unwrap_or(0)
+}
+
+trait Overlap: PartialEq {
+    /// 空行、分隔线不算有效项
+    fn is_significant(&self) -> bool;
+}
+
+impl Overlap for &str {
+    fn is_significant(&self) -> bool {
+        !self.trim().is_empty()
+    }
+}
+
+impl Overlap for ContentBlock {
+    fn is_significant(&self) -> bool {
+        !matches!(self, ContentBlock::Separator)
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::deserialize, BookCore, BookCoreError};
+
+/// 传给书源 `comments` 的参数
+///
+/// `paragraph` 为段落序号，从 0 开始；章节的 `commentBeginAtTitle` 为 true 时 0 表示标题。
+/// 为空时获取整章的评论
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommentQuery {
+    pub bid: String,
+    pub cid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paragraph: Option<u32>,
+    pub page: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Value>,
+}
+
+impl CommentQuery {
+    pub fn new(bid: impl Into<String>, cid: impl Into<String>) -> Self {
+        CommentQuery {
+            bid: bid.into(),
+            cid: cid.into(),
+            paragraph: None,
+            page: 1,
+            cursor: None,
+        }
+    }
+
+    pub fn paragraph(mut self, paragraph: u32) -> Self {
+        self.paragraph = Some(paragraph);
+        self
+    }
+
+    pub fn page(mut self, page: u32) -> Self {
+        self.page = page;
+        self
+    }
+
+    /// 下一页的查询，`page` 加一并带上本页返回的游标
+    pub fn next(&self, page: &CommentPage) -> Self {
+        CommentQuery {
+            page: self.page + 1,
+            cursor: page.next_cursor.clone(),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Comment {
+    pub id: String,
+    pub content: String,
+    pub author: Option<String>,
+    pub avatar: Option<String>,
+    pub time: Option<String>,
+    #[serde(rename = "likeCount")]
+    pub like_count: Option<u64>,
+    /// 评论所属的段落
+    pub paragraph: Option<u32>,
+    /// 已加载的回复，可能只是全部回复的一部分
+    pub replies: Option<Vec<Comment>>,
+    #[serde(rename = "replyCount")]
+    pub reply_count: Option<u64>,
+}
+
+/// 一页评论
+#[derive(Debug, Clone, Serialize)]
+pub struct CommentPage {
+    pub items: Vec<Comment>,
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+    pub total: Option<u64>,
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<Value>,
+    /// 以段落序号为键的评论数，用于渲染段评角标
+    #[serde(rename = "paragraphCounts")]
+    pub paragraph_counts: BTreeMap<u32, u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCommentPage {
+    items: Vec<Comment>,
+    #[serde(rename = "hasMore")]
+    has_more: Option<bool>,
+    total: Option<u64>,
+    #[serde(rename = "nextCursor")]
+    next_cursor: Option<Value>,
+    #[serde(rename = "paragraphCounts")]
+    paragraph_counts: Option<BTreeMap<u32, u64>>,
+}
+
+impl BookCore {
+    /// 书源是否提供评论
+    pub fn has_comments(&mut self) -> bool {
+        self.has_entry("comments")
+    }
+
+    /// 获取一页章节评论或段评
+    ///
+    /// 书源的 `comments` 可以返回数组，也可以返回 `{ items, hasMore, total, nextCursor, paragraphCounts }`；
+    /// 返回数组时本页不为空即认为还有下一页，返回对象但未声明 hasMore 时以是否有游标为准
+    pub fn get_comments(&mut self, query: CommentQuery) -> Result<CommentPage, BookCoreError> {
+        let args = serde_json::to_value(&query).expect("CommentQuery is always serializable");
+        let value: Value = self.invoke("comments", args)?;
+        let raw = if value.is_array() {
+            let items: Vec<Comment> = deserialize(value)?;
+            RawCommentPage {
+                has_more: Some(!items.is_empty()),
+                items,
+                total: None,
+                next_cursor: None,
+                paragraph_counts: None,
+            }
+        } else {
+            deserialize(value)?
+        };
+        let has_more = raw.has_more.unwrap_or(raw.next_cursor.is_some());
+        Ok(CommentPage {
+            items: raw.items,
+            has_more,
+            total: raw.total,
+            next_cursor: raw.next_cursor,
+            paragraph_counts: raw.paragraph_counts.unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::{BookCoreError, Chapter};
+
+/// 章节正文中的一个块
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum ContentBlock {
+    /// 段落，`spans` 存在时以其拼接结果为准
+    #[serde(rename = "paragraph")]
+    Paragraph {
+        #[serde(default)]
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        spans: Option<Vec<TextSpan>>,
+    },
+    #[serde(rename = "heading")]
+    Heading {
+        text: String,
+        /// 1 到 6，缺省为 2
+        level: Option<u8>,
+    },
+    /// 插图，`headers` 为加载图片时需要携带的请求头，例如 Referer
+    #[serde(rename = "image")]
+    Image {
+        url: String,
+        headers: Option<BTreeMap<String, String>>,
+        alt: Option<String>,
+        width: Option<u32>,
+        height: Option<u32>,
+    },
+    #[serde(rename = "separator")]
+    Separator,
+}
+
+/// 段落内的一段带样式的文本
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct TextSpan {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+impl ContentBlock {
+    /// 块对应的纯文本，分隔线与没有说明文字的图片为空
+    pub fn plain_text(&self) -> &str {
+        match self {
+            Self::Paragraph { text, .. } | Self::Heading { text, .. } => text,
+            Self::Image { alt, .. } => alt.as_deref().unwrap_or_default(),
+            Self::Separator => "",
+        }
+    }
+}
+
+/// 校验并规范化正文块
+///
+/// 段落与标题去掉首尾空白，空段落被丢弃；标题级别限制在 1 到 6；
+/// 图片的相对地址按 `base_url` 解析，无法解析时返回错误；首尾及连续的分隔线被合并
+pub fn normalize_blocks(
+    blocks: Vec<ContentBlock>,
+    base_url: Option<&str>,
+) -> Result<Vec<ContentBlock>, BookCoreError> {
+    let base_url = base_url.and_then(|base_url| Url::parse(base_url).ok());
+    let mut normalized: Vec<ContentBlock> = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let block = match block {
+            ContentBlock::Paragraph { text, spans } => {
+                let text = match &spans {
+                    Some(spans) => spans.iter().map(|span| span.text.as_str()).collect(),
+                    None => text,
+                };
+                let text = text.trim().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                ContentBlock::Paragraph { text, spans }
+            }
+            ContentBlock::Heading { text, level } => {
+                let text = text.trim().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                ContentBlock::Heading {
+                    text,
+                    level: Some(level.unwrap_or(2).clamp(1, 6)),
+                }
+            }
+            ContentBlock::Image {
+                url,
+                headers,
+                alt,
+                width,
+                height,
+            } => {
+                let url = resolve_url(url.trim(), base_url.as_ref())?;
+                ContentBlock::Image {
+                    url,
+                    headers,
+                    alt,
+                    width,
+                    height,
+                }
+            }
+            ContentBlock::Separator => {
+                if matches!(normalized.last(), None | Some(ContentBlock::Separator)) {
+                    continue;
+                }
+                ContentBlock::Separator
+            }
+        };
+        normalized.push(block);
+    }
+    if matches!(normalized.last(), Some(ContentBlock::Separator)) {
+        normalized.pop();
+    }
+    Ok(normalized)
+}
+
+/// 把正文块转换为纯文本，块之间以换行分隔
+pub fn blocks_to_text(blocks: &[ContentBlock]) -> String {
+    blocks
+        .iter()
+        .map(ContentBlock::plain_text)
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn resolve_url(url: &str, base_url: Option<&Url>) -> Result<String, BookCoreError> {
+    let invalid = || BookCoreError::InvalidContent(format!("invalid image url `{}`", url));
+    if url.is_empty() {
+        return Err(invalid());
+    }
+    match Url::parse(url) {
+        Ok(url) => Ok(url.to_string()),
+        Err(_) => base_url
+            .and_then(|base_url| base_url.join(url).ok())
+            .map(|url| url.to_string())
+            .ok_or_else(invalid),
+    }
+}
+
+impl Chapter {
+    /// 规范化 `blocks`，并在书源只返回了块时用其纯文本填充 `content`
+    pub(crate) fn normalize(&mut self, base_url: Option<&str>) -> Result<(), BookCoreError> {
+        let Some(blocks) = self.blocks.take() else {
+            return Ok(());
+        };
+        let blocks = normalize_blocks(blocks, base_url)?;
+        if self.content.is_empty() {
+            self.content = blocks_to_text(&blocks);
+        }
+        self.blocks = Some(blocks);
+        Ok(())
+    }
+}
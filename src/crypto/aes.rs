@@ -3,8 +3,8 @@ use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit, StreamCipher};
 use base64::prelude::BASE64_STANDARD;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use boa_engine::{
-    class::Class, js_error, js_string, Context, JsData, JsNativeError, JsObject, JsResult, JsValue,
-    NativeFunction,
+    class::Class, js_error, js_string, Context, JsArgs, JsData, JsNativeError, JsObject, JsResult,
+    JsValue, NativeFunction,
 };
 use boa_gc::{Finalize, Trace};
 
@@ -39,11 +39,13 @@ struct Aes {
 
 impl Aes {
     pub fn form_js_value(_this: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<Self> {
-        let options = args.get(0).unwrap();
+        let options = args.get_or_undefined(0);
         if options.is_null_or_undefined() {
             return Err(js_error!("options 不能为空"));
         }
-        let options = options.as_object().unwrap();
+        let options = options
+            .as_object()
+            .ok_or_else(|| js_error!("options 应为对象"))?;
         let cipher_mode = if let Ok(mode) = options.get(js_string!("cipherMode"), ctx) {
             let val_str = mode.to_string(ctx)?.to_std_string_escaped();
             match val_str.as_str() {
@@ -57,7 +59,7 @@ impl Aes {
         };
         // 读取 aes_type
         let aes_type = if let Ok(aes_type) = options.get(js_string!("aesType"), ctx) {
-            let val_str = aes_type.to_string(ctx).unwrap().to_std_string_escaped();
+            let val_str = aes_type.to_string(ctx)?.to_std_string_escaped();
             match val_str.as_str() {
                 "aes128" => AesType::Aes128,
                 "aes192" => AesType::Aes192,
@@ -69,7 +71,7 @@ impl Aes {
         };
         // 读取 padding_type
         let padding_type = if let Ok(padding_type) = options.get(js_string!("paddingType"), ctx) {
-            let val_str = padding_type.to_string(ctx).unwrap().to_std_string_escaped();
+            let val_str = padding_type.to_string(ctx)?.to_std_string_escaped();
             match val_str.as_str() {
                 "nopadding" => PaddingType::NoPadding,
                 "pkcs7" => PaddingType::Pkcs7,
@@ -84,7 +86,7 @@ impl Aes {
         };
         // 读取 encoding
         let encoding = if let Ok(encoding) = options.get(js_string!("encoding"), ctx) {
-            let val_str = encoding.to_string(ctx).unwrap().to_std_string_escaped();
+            let val_str = encoding.to_string(ctx)?.to_std_string_escaped();
             match val_str.as_str() {
                 "base64" => Encoding::Base64,
                 "hex" => Encoding::Hex,
@@ -113,12 +115,20 @@ impl Aes {
                 }
                 key_buf
             } else {
-                let val_str = key.to_string(ctx).unwrap().to_std_string_escaped();
+                let val_str = key.to_string(ctx)?.to_std_string_escaped();
                 val_str.into_bytes()
             }
         } else {
             return Err(js_error!("key 不能为空"));
         };
+        let key_len = match aes_type {
+            AesType::Aes128 => 16,
+            AesType::Aes192 => 24,
+            AesType::Aes256 => 32,
+        };
+        if key.len() < key_len {
+            return Err(js_error!("key 长度不足"));
+        }
         // 读取 iv
         let iv: Vec<u8> = if let Ok(_iv) = options.get(js_string!("iv"), ctx) {
             if _iv.is_string() {
@@ -230,22 +240,31 @@ impl Aes {
             AesType::Aes128 => {
                 let key = &key[..16];
                 let mut buf1 = vec![0u8; buf.len()];
-                let mut cipher = Aes128Ofb::new(key.into(), iv.into());
-                cipher.apply_keystream_b2b(buf, &mut buf1).unwrap();
+                let mut cipher = Aes128Ofb::new_from_slices(key, iv)
+                    .map_err(|_| js_error!("aes128 ofb new error"))?;
+                cipher
+                    .apply_keystream_b2b(buf, &mut buf1)
+                    .map_err(|_| js_error!("aes128 ofb error"))?;
                 Ok(buf1)
             }
             AesType::Aes192 => {
                 let key = &key[..24];
                 let mut buf1 = vec![0u8; buf.len()];
-                let mut cipher = Aes192Ofb::new(key.into(), iv.into());
-                cipher.apply_keystream_b2b(buf, &mut buf1).unwrap();
+                let mut cipher = Aes192Ofb::new_from_slices(key, iv)
+                    .map_err(|_| js_error!("aes192 ofb new error"))?;
+                cipher
+                    .apply_keystream_b2b(buf, &mut buf1)
+                    .map_err(|_| js_error!("aes192 ofb error"))?;
                 Ok(buf1)
             }
             AesType::Aes256 => {
                 let key = &key[..32];
                 let mut buf1 = vec![0u8; buf.len()];
-                let mut cipher = Aes256Ofb::new(key.into(), iv.into());
-                cipher.apply_keystream_b2b(buf, &mut buf1).unwrap();
+                let mut cipher = Aes256Ofb::new_from_slices(key, iv)
+                    .map_err(|_| js_error!("aes256 ofb new error"))?;
+                cipher
+                    .apply_keystream_b2b(buf, &mut buf1)
+                    .map_err(|_| js_error!("aes256 ofb error"))?;
                 Ok(buf1)
             }
         }
@@ -300,7 +319,10 @@ impl Aes {
                 JsNativeError::typ()
                     .with_message("get Decrypt.prototype.decrypt called with invalid `this`")
             })?;
-        let origin_text = args.get(0).unwrap().to_string(ctx)?.to_std_string_escaped();
+        let origin_text = args
+            .get_or_undefined(0)
+            .to_string(ctx)?
+            .to_std_string_escaped();
         let plaintext = origin_text.as_bytes();
         let pt_len = plaintext.len();
         let mut buf = vec![0u8; pt_len + 16];
@@ -392,22 +414,31 @@ impl Aes {
             AesType::Aes128 => {
                 let key = &key[..16];
                 let mut buf1 = vec![0u8; buf.len()];
-                let mut cipher = Aes128Ofb::new(key.into(), iv.into());
-                cipher.apply_keystream_b2b(buf, &mut buf1).unwrap();
+                let mut cipher = Aes128Ofb::new_from_slices(key, iv)
+                    .map_err(|_| js_error!("aes128 ofb new error"))?;
+                cipher
+                    .apply_keystream_b2b(buf, &mut buf1)
+                    .map_err(|_| js_error!("aes128 ofb error"))?;
                 Ok(buf1)
             }
             AesType::Aes192 => {
                 let key = &key[..24];
                 let mut buf1 = vec![0u8; buf.len()];
-                let mut cipher = Aes192Ofb::new(key.into(), iv.into());
-                cipher.apply_keystream_b2b(buf, &mut buf1).unwrap();
+                let mut cipher = Aes192Ofb::new_from_slices(key, iv)
+                    .map_err(|_| js_error!("aes192 ofb new error"))?;
+                cipher
+                    .apply_keystream_b2b(buf, &mut buf1)
+                    .map_err(|_| js_error!("aes192 ofb error"))?;
                 Ok(buf1)
             }
             AesType::Aes256 => {
                 let key = &key[..32];
                 let mut buf1 = vec![0u8; buf.len()];
-                let mut cipher = Aes256Ofb::new(key.into(), iv.into());
-                cipher.apply_keystream_b2b(buf, &mut buf1).unwrap();
+                let mut cipher = Aes256Ofb::new_from_slices(key, iv)
+                    .map_err(|_| js_error!("aes256 ofb new error"))?;
+                cipher
+                    .apply_keystream_b2b(buf, &mut buf1)
+                    .map_err(|_| js_error!("aes256 ofb error"))?;
                 Ok(buf1)
             }
         }
@@ -462,9 +493,14 @@ impl Aes {
                 JsNativeError::typ()
                     .with_message("get Decrypt.prototype.decrypt called with invalid `this`")
             })?;
-        let encrypted_data = args.get(0).unwrap().to_string(ctx)?.to_std_string_escaped();
+        let encrypted_data = args
+            .get_or_undefined(0)
+            .to_string(ctx)?
+            .to_std_string_escaped();
         let mut encrypted_bytes = match options.encoding {
-            Encoding::Base64 => BASE64_STANDARD.decode(&encrypted_data).unwrap(),
+            Encoding::Base64 => BASE64_STANDARD
+                .decode(&encrypted_data)
+                .map_err(|_| js_error!("base64 decode error"))?,
             Encoding::Hex => {
                 hex::decode(encrypted_data.as_bytes()).map_err(|_| js_error!("hex decode error"))?
             }
@@ -1,8 +1,8 @@
 use base64::prelude::BASE64_STANDARD;
 use base64::prelude::*;
 use boa_engine::{
-    class::Class, js_error, js_string, Context, JsData, JsNativeError, JsObject, JsResult, JsValue,
-    NativeFunction,
+    class::Class, js_error, js_string, Context, JsArgs, JsData, JsNativeError, JsObject, JsResult,
+    JsValue, NativeFunction,
 };
 use boa_gc::{Finalize, Trace};
 use hmac::Mac;
@@ -40,11 +40,13 @@ struct Hmac {
 
 impl Hmac {
     fn from_js_value(_this: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<Self> {
-        let options = args.get(0).unwrap();
+        let options = args.get_or_undefined(0);
         if options.is_null_or_undefined() {
             return Err(js_error!("options 不能为空"));
         }
-        let options = options.as_object().unwrap();
+        let options = options
+            .as_object()
+            .ok_or_else(|| js_error!("options 应为对象"))?;
         let hash = options.get(js_string!("hash"), ctx)?;
         let hash = if hash.is_string() {
             let hash = hash.to_string(ctx)?.to_std_string_escaped();
@@ -89,7 +91,10 @@ impl Hmac {
                 JsNativeError::typ()
                     .with_message("get Hmac.prototype.encrypt called with invalid `this`")
             })?;
-        let origin_text = args.get(0).unwrap().to_string(ctx)?.to_std_string_escaped();
+        let origin_text = args
+            .get_or_undefined(0)
+            .to_string(ctx)?
+            .to_std_string_escaped();
         let plaintext = origin_text.as_bytes();
         let hmac_key = options.key.as_bytes();
         let encoding = &options.encoding;
@@ -0,0 +1,286 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+use crate::{CatalogChapter, CatalogVolume};
+
+/// 按名称匹配章节时默认要求的最低相似度
+pub const DEFAULT_NAME_SIMILARITY: f64 = 0.8;
+
+/// 目录中的一章及其所在的卷
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChapterEntry {
+    #[serde(rename = "volumeId")]
+    pub volume_id: String,
+    pub chapter: CatalogChapter,
+}
+
+/// 新旧目录中对应的同一章
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChapterChange {
+    pub old: ChapterEntry,
+    pub new: ChapterEntry,
+}
+
+/// 两份目录之间的差异
+///
+/// `removed` 按旧目录中的顺序排列，其余按新目录中的顺序排列；同一章可能同时出现在
+/// `reissued`、`renamed` 与 `unlocked` 中
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CatalogDiff {
+    pub added: Vec<ChapterEntry>,
+    pub removed: Vec<ChapterEntry>,
+    /// 名称改变的章节，首尾空白的差异不计入
+    pub renamed: Vec<ChapterChange>,
+    /// `canRead` 由 false 变为 true 的章节
+    pub unlocked: Vec<ChapterChange>,
+    /// id 改变、按名称匹配上的章节
+    pub reissued: Vec<ChapterChange>,
+}
+
+impl CatalogDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.unlocked.is_empty()
+            && self.reissued.is_empty()
+    }
+}
+
+/// 比较本地保存的目录与新获取的目录，见 `diff_catalog_with`
+pub fn diff_catalog(old: &[CatalogVolume], new: &[CatalogVolume]) -> CatalogDiff {
+    diff_catalog_with(old, new, DEFAULT_NAME_SIMILARITY)
+}
+
+/// 比较两份目录
+///
+/// 章节先按 id 匹配；剩下的章节再按名称匹配，名称完全相同（忽略空白与大小写）的优先，
+/// 其次是相似度不低于 `similarity` 且名称中的数字完全一致的，相似度相同时取旧目录中靠前的一章
+pub fn diff_catalog_with(
+    old: &[CatalogVolume],
+    new: &[CatalogVolume],
+    similarity: f64,
+) -> CatalogDiff {
+    let old = flatten(old);
+    let new = flatten(new);
+    let old_names: Vec<String> = old
+        .iter()
+        .map(|entry| normalize_name(&entry.chapter.name))
+        .collect();
+    let mut matches: Vec<Option<usize>> = vec![None; new.len()];
+    let mut used = vec![false; old.len()];
+
+    let mut by_id: HashMap<&str, usize> = HashMap::new();
+    for (index, entry) in old.iter().enumerate() {
+        by_id.entry(entry.chapter.id.as_str()).or_insert(index);
+    }
+    for (matched, entry) in matches.iter_mut().zip(&new) {
+        if let Some(&index) = by_id.get(entry.chapter.id.as_str()) {
+            if !used[index] {
+                used[index] = true;
+                *matched = Some(index);
+            }
+        }
+    }
+
+    let mut by_name: HashMap<&str, VecDeque<usize>> = HashMap::new();
+    for (index, name) in old_names.iter().enumerate() {
+        if !used[index] {
+            by_name.entry(name).or_default().push_back(index);
+        }
+    }
+    for (matched, entry) in matches.iter_mut().zip(&new) {
+        if matched.is_some() {
+            continue;
+        }
+        let name = normalize_name(&entry.chapter.name);
+        if let Some(index) = by_name.get_mut(name.as_str()).and_then(VecDeque::pop_front) {
+            used[index] = true;
+            *matched = Some(index);
+        }
+    }
+
+    for (matched, entry) in matches.iter_mut().zip(&new) {
+        if matched.is_some() {
+            continue;
+        }
+        let name = normalize_name(&entry.chapter.name);
+        let best = old_names
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !used[*index])
+            .map(|(index, old_name)| (index, name_similarity(&name, old_name)))
+            .filter(|(_, score)| *score >= similarity)
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)));
+        if let Some((index, _)) = best {
+            used[index] = true;
+            *matched = Some(index);
+        }
+    }
+
+    let mut diff = CatalogDiff::default();
+    for (entry, matched) in new.into_iter().zip(matches) {
+        let Some(index) = matched else {
+            diff.added.push(entry);
+            continue;
+        };
+        let previous = &old[index];
+        let change = || ChapterChange {
+            old: previous.clone(),
+            new: entry.clone(),
+        };
+        if previous.chapter.id != entry.chapter.id {
+            diff.reissued.push(change());
+        }
+        if previous.chapter.name.trim() != entry.chapter.name.trim() {
+            diff.renamed.push(change());
+        }
+        if previous.chapter.can_read == Some(false) && entry.chapter.can_read == Some(true) {
+            diff.unlocked.push(change());
+        }
+    }
+    diff.removed = old
+        .into_iter()
+        .zip(used)
+        .filter(|(_, used)| !used)
+        .map(|(entry, _)| entry)
+        .collect();
+    diff
+}
+
+fn flatten(volumes: &[CatalogVolume]) -> Vec<ChapterEntry> {
+    volumes
+        .iter()
+        .flat_map(|volume| {
+            volume.chapters.iter().map(|chapter| ChapterEntry {
+                volume_id: volume.id.clone(),
+                chapter: chapter.clone(),
+            })
+        })
+        .collect()
+}
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 基于编辑距离的相似度，0 到 1；名称中的数字不一致时为 0，避免“第12章”与“第13章”、
+/// “第一百二十二章”与“第一百二十三章”被匹配
+fn name_similarity(a: &str, b: &str) -> f64 {
+    if numbers(a) != numbers(b) {
+        return 0.0;
+    }
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(&a, &b) as f64 / len as f64
+}
+
+enum Numeral {
+    Digit(u64),
+    Unit(u64),
+}
+
+fn numeral(c: char) -> Option<Numeral> {
+    let digit = match c {
+        '0'..='9' => c as u64 - '0' as u64,
+        '０'..='９' => c as u64 - '０' as u64,
+        '零' | '〇' => 0,
+        '一' | '壹' => 1,
+        '二' | '两' | '贰' => 2,
+        '三' | '叁' => 3,
+        '四' | '肆' => 4,
+        '五' | '伍' => 5,
+        '六' | '陆' => 6,
+        '七' | '柒' => 7,
+        '八' | '捌' => 8,
+        '九' | '玖' => 9,
+        '十' | '拾' => return Some(Numeral::Unit(10)),
+        '百' | '佰' => return Some(Numeral::Unit(100)),
+        '千' | '仟' => return Some(Numeral::Unit(1_000)),
+        '万' => return Some(Numeral::Unit(10_000)),
+        '亿' => return Some(Numeral::Unit(100_000_000)),
+        _ => return None,
+    };
+    Some(Numeral::Digit(digit))
+}
+
+/// 名称中的数字，阿拉伯数字、全角数字与中文数字统一为不带前导零的十进制字符串
+fn numbers(name: &str) -> Vec<String> {
+    let mut numbers = Vec::new();
+    let mut run = Vec::new();
+    for c in name.chars().chain(Some(' ')) {
+        match numeral(c) {
+            Some(numeral) => run.push(numeral),
+            None if !run.is_empty() => numbers.push(parse_numerals(&std::mem::take(&mut run))),
+            None => {}
+        }
+    }
+    numbers
+}
+
+fn parse_numerals(run: &[Numeral]) -> String {
+    // 不含单位时按位读，如“123”“二〇二四”
+    if run
+        .iter()
+        .all(|numeral| matches!(numeral, Numeral::Digit(_)))
+    {
+        let digits: String = run
+            .iter()
+            .filter_map(|numeral| match numeral {
+                Numeral::Digit(digit) => char::from_digit(*digit as u32, 10),
+                Numeral::Unit(_) => None,
+            })
+            .collect();
+        return digits.trim_start_matches('0').to_string();
+    }
+    let (mut total, mut section, mut digit) = (0u64, 0u64, 0u64);
+    for numeral in run {
+        match *numeral {
+            Numeral::Digit(value) => digit = value,
+            // 万、亿把之前的部分整体放大
+            Numeral::Unit(unit) if unit >= 10_000 => {
+                total = total
+                    .saturating_add(section)
+                    .saturating_add(digit)
+                    .saturating_mul(unit);
+                section = 0;
+                digit = 0;
+            }
+            // “十二”省略了前面的“一”
+            Numeral::Unit(unit) => {
+                section = section.saturating_add(digit.max(1).saturating_mul(unit));
+                digit = 0;
+            }
+        }
+    }
+    match total.saturating_add(section).saturating_add(digit) {
+        0 => String::new(),
+        value => value.to_string(),
+    }
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                diagonal.min(row[j]).min(current) + 1
+            };
+            diagonal = current;
+        }
+    }
+    row[b.len()]
+}
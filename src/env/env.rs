@@ -166,7 +166,6 @@ pub fn regist_envs(ctx: &mut Context) {
         __ENVS__[key] = value;
       }
       function getEnv(key){
-        console.log(__ENVS__, key,__ENVS__[0]);
         return __ENVS__[key];
       }
       function setEnvs(envs){ __ENVS__ = envs}
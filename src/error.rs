@@ -0,0 +1,162 @@
+use std::fmt;
+
+use boa_engine::{js_string, Context, JsError, JsValue};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// BookCore 对外暴露的错误类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookCoreError {
+    /// 书源脚本存在语法错误，无法解析
+    Syntax(String),
+    /// 脚本执行过程中抛出的异常
+    Exception { name: String, message: String },
+    /// 脚本返回值无法转换为目标结构，`path` 为出错字段的 JSON 路径
+    Deserialize { path: String, message: String },
+    /// JReqwest 发起的网络请求失败
+    Network(String),
+    /// 书源未定义对应的入口
+    MissingEntry(String),
+    /// 搜索筛选项的取值不符合书源的声明
+    InvalidFilter { id: String, message: String },
+    /// 二维码内容无法编码或渲染
+    QrCode(String),
+    /// 章节、目录或书架的分页数超过上限
+    TooManyPages(usize),
+    /// 书架第几页返回了已经出现过的 continuation，继续翻页会陷入循环
+    RepeatedContinuation(u32),
+    /// 章节正文块无法规范化，例如图片地址无效
+    InvalidContent(String),
+    /// 书源未声明对应的表单
+    FormNotFound(String),
+    /// 书源未声明对应的发现页分区
+    SectionNotFound(String),
+    /// 入口返回的 Promise 在任务队列清空后仍未完成
+    PromisePending,
+    /// 超出循环次数或递归深度上限
+    LimitExceeded(String),
+    /// 超出单次调用的最长耗时
+    Timeout,
+    /// 调用被 CancellationToken 取消
+    Cancelled,
+    /// 持有 BookCore 的工作线程已经退出
+    Disconnected,
+    /// metadata.uuid 不是合法的 uuid
+    InvalidUuid(String),
+    /// 同一书源重复加载
+    DuplicateSource(String),
+    /// 另一份脚本声明了已被占用的 uuid
+    SourceConflict(String),
+    /// 书源未加载
+    SourceNotFound(String),
+    /// 书源已被禁用
+    SourceDisabled(String),
+    /// 替换书源时新版本不高于已加载版本
+    OutdatedVersion {
+        uuid: String,
+        loaded: String,
+        offered: String,
+    },
+}
+
+impl BookCoreError {
+    /// 脚本解析失败时的错误
+    pub(crate) fn syntax(err: JsError) -> Self {
+        match err.as_native() {
+            Some(native) => Self::Syntax(native.message().to_string()),
+            None => Self::Syntax(err.to_string()),
+        }
+    }
+
+    /// 将 boa 抛出的错误转换为 BookCoreError
+    pub(crate) fn from_js(err: JsError, ctx: &mut Context) -> Self {
+        if let Some(native) = err.as_native() {
+            if native.is_runtime_limit() {
+                return Self::LimitExceeded(native.message().to_string());
+            }
+            return Self::Exception {
+                name: native.kind.to_string(),
+                message: native.message().to_string(),
+            };
+        }
+        let value = err.to_opaque(ctx);
+        let name = string_property(&value, "name", ctx).unwrap_or_else(|| "Error".to_string());
+        let message =
+            string_property(&value, "message", ctx).unwrap_or_else(|| value.display().to_string());
+        if name == "NetworkError" {
+            Self::Network(message)
+        } else {
+            Self::Exception { name, message }
+        }
+    }
+}
+
+impl fmt::Display for BookCoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(message) => write!(f, "SyntaxError: {}", message),
+            Self::Exception { name, message } => write!(f, "{}: {}", name, message),
+            Self::Deserialize { path, message } => {
+                write!(f, "failed to deserialize `{}`: {}", path, message)
+            }
+            Self::Network(message) => write!(f, "NetworkError: {}", message),
+            Self::MissingEntry(entry) => write!(f, "entry `{}` is not defined", entry),
+            Self::InvalidFilter { id, message } => {
+                write!(f, "invalid value for filter `{}`: {}", id, message)
+            }
+            Self::QrCode(message) => write!(f, "failed to render qr code: {}", message),
+            Self::TooManyPages(limit) => {
+                write!(f, "more than {} pages", limit)
+            }
+            Self::RepeatedContinuation(page) => {
+                write!(f, "continuation returned on page {} was already seen", page)
+            }
+            Self::InvalidContent(message) => write!(f, "invalid chapter content: {}", message),
+            Self::FormNotFound(form) => write!(f, "form `{}` is not declared", form),
+            Self::SectionNotFound(id) => write!(f, "explore section `{}` is not declared", id),
+            Self::PromisePending => write!(f, "promise returned by the script never settled"),
+            Self::LimitExceeded(message) => write!(f, "execution limit exceeded: {}", message),
+            Self::Timeout => write!(f, "execution deadline exceeded"),
+            Self::Cancelled => write!(f, "execution was cancelled"),
+            Self::Disconnected => write!(f, "book core worker thread has stopped"),
+            Self::InvalidUuid(uuid) => write!(f, "`{}` is not a valid uuid", uuid),
+            Self::DuplicateSource(uuid) => write!(f, "source `{}` is already loaded", uuid),
+            Self::SourceConflict(uuid) => {
+                write!(f, "uuid `{}` is already used by another source", uuid)
+            }
+            Self::SourceNotFound(uuid) => write!(f, "source `{}` is not loaded", uuid),
+            Self::SourceDisabled(uuid) => write!(f, "source `{}` is disabled", uuid),
+            Self::OutdatedVersion {
+                uuid,
+                loaded,
+                offered,
+            } => write!(
+                f,
+                "source `{}` version {} is not newer than loaded version {}",
+                uuid, offered, loaded
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BookCoreError {}
+
+/// 反序列化脚本返回值，出错时记录字段路径
+pub(crate) fn deserialize<T: DeserializeOwned>(value: Value) -> Result<T, BookCoreError> {
+    serde_path_to_error::deserialize(value).map_err(|err| BookCoreError::Deserialize {
+        path: err.path().to_string(),
+        message: err.inner().to_string(),
+    })
+}
+
+fn string_property(value: &JsValue, key: &str, ctx: &mut Context) -> Option<String> {
+    let object = value.as_object()?;
+    let property = object.get(js_string!(key), ctx).ok()?;
+    if property.is_null_or_undefined() {
+        return None;
+    }
+    property
+        .to_string(ctx)
+        .ok()
+        .map(|property| property.to_std_string_escaped())
+}
@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    search::{validate_filters, FilterValue, SearchFilter, SearchPage},
+    BookCore, BookCoreError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ExploreSectionType {
+    /// 排行榜
+    #[serde(rename = "rank")]
+    Rank,
+    /// 最新上架、最近更新
+    #[serde(rename = "latest")]
+    Latest,
+    /// 分类
+    #[serde(rename = "category")]
+    Category,
+    /// 推荐
+    #[serde(rename = "recommend")]
+    Recommend,
+    #[serde(other, rename = "other")]
+    Other,
+}
+
+/// 书源通过 `exploreSections` 声明的发现页分区
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExploreSection {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "sectionType")]
+    pub section_type: ExploreSectionType,
+    /// 分区内可用的筛选项，例如分类、榜单周期
+    pub filters: Option<Vec<SearchFilter>>,
+}
+
+/// 传给书源 `explore` 的参数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExploreQuery {
+    #[serde(rename = "sectionId")]
+    pub section_id: String,
+    pub page: u32,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub filters: BTreeMap<String, FilterValue>,
+}
+
+impl ExploreQuery {
+    pub fn new(section_id: impl Into<String>) -> Self {
+        ExploreQuery {
+            section_id: section_id.into(),
+            page: 1,
+            filters: BTreeMap::new(),
+        }
+    }
+
+    pub fn page(mut self, page: u32) -> Self {
+        self.page = page;
+        self
+    }
+
+    pub fn filter(mut self, id: impl Into<String>, value: FilterValue) -> Self {
+        self.filters.insert(id.into(), value);
+        self
+    }
+}
+
+impl BookCore {
+    /// 书源是否提供发现页，需要同时声明 `exploreSections` 与 `explore`
+    pub fn has_explore(&mut self) -> bool {
+        self.has_entry("exploreSections") && self.has_entry("explore")
+    }
+
+    /// 发现页分区，书源不支持发现页时为空
+    pub fn get_explore_sections(&mut self) -> Result<Vec<ExploreSection>, BookCoreError> {
+        Ok(self.read_optional("exploreSections")?.unwrap_or_default())
+    }
+
+    pub fn get_explore(
+        &mut self,
+        section_id: String,
+        page: u32,
+    ) -> Result<SearchPage, BookCoreError> {
+        self.explore(ExploreQuery::new(section_id).page(page))
+    }
+
+    /// 获取发现页分区的一页书籍，返回值格式与 `search` 相同
+    pub fn explore(&mut self, query: ExploreQuery) -> Result<SearchPage, BookCoreError> {
+        let sections = self.get_explore_sections()?;
+        let Some(section) = sections
+            .iter()
+            .find(|section| section.id == query.section_id)
+        else {
+            return Err(BookCoreError::SectionNotFound(query.section_id));
+        };
+        validate_filters(
+            section.filters.as_deref().unwrap_or_default(),
+            &query.filters,
+        )?;
+        let args = serde_json::to_value(&query).expect("ExploreQuery is always serializable");
+        let value = self.invoke("explore", args)?;
+        SearchPage::from_value(value, None)
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use regex::Regex;
+
+use crate::{error::deserialize, BookCore, BookCoreError, Form, FormField, FormFieldType};
+
+/// 提交表单的结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormOutcome {
+    Success {
+        message: Option<String>,
+    },
+    /// 处理函数明确返回了失败，例如账号密码错误
+    Failed {
+        message: Option<String>,
+    },
+    /// 以字段名为键的错误信息，包括 rust 侧校验失败的字段
+    FieldErrors(BTreeMap<String, String>),
+    /// 需要继续填写的表单，例如验证码、二次验证
+    NextForm(Form),
+}
+
+/// 表单处理函数的返回值，所有字段都可以省略
+#[derive(Debug, Default, Deserialize)]
+struct RawFormResult {
+    success: Option<bool>,
+    message: Option<String>,
+    envs: Option<Map<String, Value>>,
+    errors: Option<BTreeMap<String, String>>,
+    form: Option<Form>,
+}
+
+impl Form {
+    /// 表单的标识，未声明 id 时使用标题
+    pub fn key(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.title)
+    }
+
+    /// 补全默认值并去掉隐藏字段与按钮，得到实际提交的取值
+    pub fn normalize(&self, values: &Map<String, Value>) -> Map<String, Value> {
+        let mut merged = values.clone();
+        for field in &self.fields {
+            if let Some(default) = &field.default {
+                merged
+                    .entry(field.field.clone())
+                    .or_insert_with(|| default.clone());
+            }
+        }
+        let mut normalized = Map::new();
+        for (key, value) in merged.iter() {
+            match self.fields.iter().find(|field| &field.field == key) {
+                Some(field) if matches!(field.field_type, FormFieldType::Button) => {}
+                Some(field) if !field.is_visible(&merged) => {}
+                _ => {
+                    normalized.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        normalized
+    }
+
+    /// 校验提交的取值，返回以字段名为键的错误信息
+    ///
+    /// 取值应先经过 `normalize`，未声明的字段、类型不符、不在可选项内、不匹配 pattern
+    /// 以及显示中的必填字段为空都视为错误
+    pub fn validate(&self, values: &Map<String, Value>) -> BTreeMap<String, String> {
+        let mut errors = BTreeMap::new();
+        for (key, value) in values {
+            let result = match self.fields.iter().find(|field| &field.field == key) {
+                Some(field) => field.validate(value),
+                None => Err("unknown field".to_string()),
+            };
+            if let Err(message) = result {
+                errors.insert(key.clone(), message);
+            }
+        }
+        for field in &self.fields {
+            let empty = match values.get(&field.field) {
+                None | Some(Value::Null) => true,
+                Some(Value::String(value)) => value.is_empty(),
+                Some(_) => false,
+            };
+            if field.required == Some(true) && empty && field.is_visible(values) {
+                errors
+                    .entry(field.field.clone())
+                    .or_insert_with(|| "required".to_string());
+            }
+        }
+        errors
+    }
+
+    /// 字段取值是否保存到 envs，未声明的字段默认保存
+    fn persists(&self, key: &str) -> bool {
+        self.fields
+            .iter()
+            .find(|field| field.field == key)
+            .is_none_or(|field| field.persist != Some(false))
+    }
+
+    fn handler(&self) -> Option<&str> {
+        if let Some(handler) = &self.handler {
+            return Some(handler);
+        }
+        let mut buttons = self
+            .fields
+            .iter()
+            .filter(|field| matches!(field.field_type, FormFieldType::Button));
+        match (buttons.next(), buttons.next()) {
+            (Some(button), None) => Some(&button.field),
+            _ => None,
+        }
+    }
+}
+
+impl FormField {
+    /// 根据其他字段的取值判断该字段是否显示
+    pub fn is_visible(&self, values: &Map<String, Value>) -> bool {
+        self.visible_when.iter().flatten().all(|condition| {
+            let value = values.get(&condition.field).unwrap_or(&Value::Null);
+            condition
+                .equals
+                .as_ref()
+                .is_none_or(|equals| equals == value)
+                && condition
+                    .one_of
+                    .as_ref()
+                    .is_none_or(|one_of| one_of.contains(value))
+        })
+    }
+
+    /// 校验单个取值，空值是否允许由 `required` 决定
+    pub fn validate(&self, value: &Value) -> Result<(), String> {
+        if value.is_null() {
+            return Ok(());
+        }
+        match self.field_type {
+            FormFieldType::Input => {
+                let Some(value) = value.as_str() else {
+                    return Err("expected a string".to_string());
+                };
+                if let (Some(pattern), false) = (&self.pattern, value.is_empty()) {
+                    let regex = Regex::new(&format!("^(?:{})$", pattern))
+                        .map_err(|_| format!("invalid pattern `{}`", pattern))?;
+                    if !regex.is_match(value) {
+                        return Err(format!("does not match `{}`", pattern));
+                    }
+                }
+            }
+            FormFieldType::Select => {
+                let Some(value) = value.as_str() else {
+                    return Err("expected a string".to_string());
+                };
+                if let Some(options) = &self.options {
+                    if !value.is_empty() && !options.iter().any(|option| option.value == value) {
+                        return Err(format!("unknown option `{}`", value));
+                    }
+                }
+            }
+            FormFieldType::Checkbox => {
+                if !value.is_boolean() {
+                    return Err("expected a boolean".to_string());
+                }
+            }
+            FormFieldType::Button => return Err("buttons do not take a value".to_string()),
+        }
+        Ok(())
+    }
+}
+
+impl BookCore {
+    /// 按 id 或标题提交 `forms` 中声明的表单，见 `submit`
+    pub fn submit_form(
+        &mut self,
+        form: &str,
+        values: Map<String, Value>,
+    ) -> Result<FormOutcome, BookCoreError> {
+        let forms = self.get_forms()?;
+        let Some(form) = forms
+            .into_iter()
+            .find(|item| item.id.as_deref() == Some(form) || item.title == form)
+        else {
+            return Err(BookCoreError::FormNotFound(form.to_string()));
+        };
+        self.submit(&form, values)
+    }
+
+    /// 提交表单
+    ///
+    /// 取值先补全默认值、去掉隐藏字段，校验通过后按字段名写入 envs，再以取值为参数调用表单的处理函数，
+    /// 处理函数返回的 `envs` 同样写入 envs。没有处理函数的表单只保存取值。
+    /// 声明了 `persist: false` 的字段只在处理函数执行期间可见，结束后恢复为提交前的值
+    pub fn submit(
+        &mut self,
+        form: &Form,
+        values: Map<String, Value>,
+    ) -> Result<FormOutcome, BookCoreError> {
+        let values = form.normalize(&values);
+        let errors = form.validate(&values);
+        if !errors.is_empty() {
+            return Ok(FormOutcome::FieldErrors(errors));
+        }
+        let mut transient = Vec::new();
+        for (key, value) in &values {
+            if !form.persists(key) {
+                transient.push((key.clone(), self.get_env(key.clone())?));
+            }
+            self.set_env(key.clone(), value.clone())?;
+        }
+        let result = match form.handler() {
+            Some(handler) => self
+                .invoke::<Value>(handler, Value::Object(values))
+                .map(Some),
+            None => Ok(None),
+        };
+        for (key, previous) in transient {
+            self.set_env(key, previous)?;
+        }
+        let Some(value) = result? else {
+            return Ok(FormOutcome::Success { message: None });
+        };
+        let result: RawFormResult = if value.is_object() {
+            deserialize(value)?
+        } else {
+            RawFormResult {
+                success: value.as_bool(),
+                ..RawFormResult::default()
+            }
+        };
+        let RawFormResult {
+            success,
+            message,
+            envs,
+            errors,
+            form,
+        } = result;
+        for (key, value) in envs.unwrap_or_default() {
+            self.set_env(key, value)?;
+        }
+        Ok(match (errors, form, success) {
+            (Some(errors), _, _) if !errors.is_empty() => FormOutcome::FieldErrors(errors),
+            (_, Some(form), _) => FormOutcome::NextForm(form),
+            (_, _, Some(false)) => FormOutcome::Failed { message },
+            _ => FormOutcome::Success { message },
+        })
+    }
+}
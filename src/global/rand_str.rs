@@ -1,9 +1,16 @@
-use boa_engine::{js_string, Context, JsValue, NativeFunction};
+use boa_engine::{js_string, Context, JsArgs, JsNativeError, JsValue, NativeFunction};
 use rand::Rng;
 
 pub fn regist_rand_str(ctx: &mut Context) {
-    let function = NativeFunction::from_fn_ptr(|_this, _args, _context| {
-        let length = _args.get(0).unwrap().as_number().unwrap() as usize;
+    let function = NativeFunction::from_fn_ptr(|_this, args, _context| {
+        let length = match args.get_or_undefined(0).as_number() {
+            Some(length) if length.is_finite() && length >= 0.0 => length as usize,
+            _ => {
+                return Err(JsNativeError::typ()
+                    .with_message("length 应为非负数")
+                    .into())
+            }
+        };
         let mut rng = rand::rng();
         let bytes: Vec<u8> = (0..length).map(|_| rng.random_range(0..255)).collect();
         Ok(JsValue::String(js_string!(hex::encode(bytes))))
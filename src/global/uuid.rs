@@ -16,10 +16,7 @@ pub fn regist_is_uuid(ctx: &mut Context) {
         if uuid_str.is_null_or_undefined() {
             return Ok(JsValue::Boolean(false));
         }
-        let uuid_str = uuid_str
-            .to_string(_context)
-            .unwrap()
-            .to_std_string_escaped();
+        let uuid_str = uuid_str.to_string(_context)?.to_std_string_escaped();
         let is_valid = Uuid::parse_str(&uuid_str).is_ok();
         Ok(JsValue::Boolean(is_valid))
     });
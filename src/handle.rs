@@ -0,0 +1,519 @@
+use std::{collections::BTreeMap, sync::mpsc, thread};
+
+use serde_json::{Map, Value};
+use tokio::{sync::oneshot, time::sleep};
+
+use crate::{
+    catalog::{next_query, Paging},
+    merge_volumes,
+    qr_login::{QrLoginStep, QrLoginWait},
+    Action, ActionResult, Balance, BookCore, BookCoreError, BookDetail, BookLatestChapter,
+    CancellationToken, CatalogPage, CatalogQuery, CatalogVolume, Chapter, ChapterPrice,
+    CommentPage, CommentQuery, ExecutionLimits, ExploreQuery, ExploreSection, Form, FormOutcome,
+    MetaData, PurchaseQuote, PurchaseResult, QrLogin, QrLoginStatus, SearchBook, SearchFilter,
+    SearchPage, SearchQuery, ShelfResult,
+};
+
+type Job = Box<dyn FnOnce(&mut BookCore) + Send>;
+
+/// BookCore 的线程安全句柄
+///
+/// BookCore 持有的 boa Context 不能跨线程，句柄会在独立线程上创建并持有它，
+/// 所有调用都通过 channel 转发到该线程执行。句柄可以随意克隆，最后一个句柄释放后线程退出。
+///
+/// 这也是异步代码使用 BookCore 的入口：`*_async` 方法只在工作线程上执行脚本并等待结果，
+/// 不会阻塞调用方所在的运行时，current-thread 运行时下同样可用
+#[derive(Debug, Clone)]
+pub struct BookCoreHandle {
+    sender: mpsc::Sender<Job>,
+}
+
+impl BookCoreHandle {
+    pub fn spawn(code: String) -> Result<Self, BookCoreError> {
+        Self::spawn_with(move || BookCore::init(code))
+    }
+
+    /// 使用自定义的初始化逻辑在工作线程上创建 BookCore
+    pub fn spawn_with<F>(init: F) -> Result<Self, BookCoreError>
+    where
+        F: FnOnce() -> Result<BookCore, BookCoreError> + Send + 'static,
+    {
+        let (init_tx, init_rx) = mpsc::channel();
+        let handle = Self::start(init, move |result| {
+            let _ = init_tx.send(result);
+        })?;
+        init_rx.recv().map_err(|_| BookCoreError::Disconnected)??;
+        Ok(handle)
+    }
+
+    /// 与 `spawn_with` 相同，但异步等待初始化完成，不会阻塞调用方所在的 worker
+    pub async fn spawn_with_async<F>(init: F) -> Result<Self, BookCoreError>
+    where
+        F: FnOnce() -> Result<BookCore, BookCoreError> + Send + 'static,
+    {
+        let (init_tx, init_rx) = oneshot::channel();
+        let handle = Self::start(init, move |result| {
+            let _ = init_tx.send(result);
+        })?;
+        init_rx.await.map_err(|_| BookCoreError::Disconnected)??;
+        Ok(handle)
+    }
+
+    fn start<F, R>(init: F, report: R) -> Result<Self, BookCoreError>
+    where
+        F: FnOnce() -> Result<BookCore, BookCoreError> + Send + 'static,
+        R: FnOnce(Result<(), BookCoreError>) + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        thread::Builder::new()
+            .name("book-core".to_string())
+            .spawn(move || {
+                let mut core = match init() {
+                    Ok(core) => {
+                        report(Ok(()));
+                        core
+                    }
+                    Err(err) => {
+                        report(Err(err));
+                        return;
+                    }
+                };
+                while let Ok(job) = receiver.recv() {
+                    job(&mut core);
+                }
+            })
+            .map_err(|_| BookCoreError::Disconnected)?;
+        Ok(Self { sender })
+    }
+
+    /// 在工作线程上执行闭包，阻塞等待结果
+    pub fn call<T, F>(&self, f: F) -> Result<T, BookCoreError>
+    where
+        F: FnOnce(&mut BookCore) -> Result<T, BookCoreError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        self.send(Box::new(move |core| {
+            let _ = tx.send(f(core));
+        }))?;
+        rx.recv().map_err(|_| BookCoreError::Disconnected)?
+    }
+
+    /// 在工作线程上执行闭包，异步等待结果，不会阻塞调用方所在的 worker
+    pub async fn call_async<T, F>(&self, f: F) -> Result<T, BookCoreError>
+    where
+        F: FnOnce(&mut BookCore) -> Result<T, BookCoreError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.send(Box::new(move |core| {
+            let _ = tx.send(f(core));
+        }))?;
+        rx.await.map_err(|_| BookCoreError::Disconnected)?
+    }
+
+    /// 与 `call` 相同，但调用绑定到 `token`，可以在其他线程上取消
+    pub fn call_cancellable<T, F>(
+        &self,
+        token: &CancellationToken,
+        f: F,
+    ) -> Result<T, BookCoreError>
+    where
+        F: FnOnce(&mut BookCore) -> Result<T, BookCoreError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let token = token.clone();
+        self.call(move |core| core.with_cancellation(&token, f))
+    }
+
+    pub async fn call_cancellable_async<T, F>(
+        &self,
+        token: &CancellationToken,
+        f: F,
+    ) -> Result<T, BookCoreError>
+    where
+        F: FnOnce(&mut BookCore) -> Result<T, BookCoreError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let token = token.clone();
+        self.call_async(move |core| core.with_cancellation(&token, f))
+            .await
+    }
+
+    pub fn get_metadata(&self) -> Result<MetaData, BookCoreError> {
+        self.call(|core| core.get_metadata())
+    }
+
+    pub fn get_forms(&self) -> Result<Vec<Form>, BookCoreError> {
+        self.call(|core| core.get_forms())
+    }
+
+    pub fn get_actions(&self) -> Result<Vec<Action>, BookCoreError> {
+        self.call(|core| core.get_actions())
+    }
+
+    pub fn run_action(&self, action: String) -> Result<Value, BookCoreError> {
+        self.call(move |core| core.run_action(action))
+    }
+
+    pub fn submit_form(
+        &self,
+        form: String,
+        values: Map<String, Value>,
+    ) -> Result<FormOutcome, BookCoreError> {
+        self.call(move |core| core.submit_form(&form, values))
+    }
+
+    pub fn perform_action(
+        &self,
+        action: String,
+        values: Map<String, Value>,
+    ) -> Result<ActionResult, BookCoreError> {
+        self.call(move |core| core.perform_action(&action, values))
+    }
+
+    pub fn has_qr_login(&self) -> Result<bool, BookCoreError> {
+        self.call(|core| Ok(core.has_qr_login()))
+    }
+
+    pub fn start_qr_login(&self) -> Result<QrLogin, BookCoreError> {
+        self.call(|core| core.start_qr_login())
+    }
+
+    pub fn poll_qr_login(&self, login: QrLogin) -> Result<QrLoginStatus, BookCoreError> {
+        self.call(move |core| core.poll_qr_login(&login))
+    }
+
+    pub fn set_limits(&self, limits: ExecutionLimits) -> Result<(), BookCoreError> {
+        self.call(move |core| {
+            core.set_limits(limits);
+            Ok(())
+        })
+    }
+
+    pub fn set_envs(&self, envs: Value) -> Result<(), BookCoreError> {
+        self.call(move |core| core.set_envs(envs))
+    }
+
+    pub fn set_env(&self, key: String, value: Value) -> Result<Value, BookCoreError> {
+        self.call(move |core| core.set_env(key, value))
+    }
+
+    pub fn get_envs(&self) -> Result<Value, BookCoreError> {
+        self.call(|core| core.get_envs())
+    }
+
+    pub fn get_env(&self, key: String) -> Result<Value, BookCoreError> {
+        self.call(move |core| core.get_env(key))
+    }
+
+    pub fn clear_envs(&self) -> Result<(), BookCoreError> {
+        self.call(|core| core.clear_envs())
+    }
+
+    pub fn search_books(
+        &self,
+        key: String,
+        page: u8,
+        count: u8,
+    ) -> Result<Vec<SearchBook>, BookCoreError> {
+        self.call(move |core| core.search_books(key, page, count))
+    }
+
+    pub fn search(&self, query: SearchQuery) -> Result<SearchPage, BookCoreError> {
+        self.call(move |core| core.search(query))
+    }
+
+    pub fn get_search_filters(&self) -> Result<Vec<SearchFilter>, BookCoreError> {
+        self.call(|core| core.get_search_filters())
+    }
+
+    pub fn has_explore(&self) -> Result<bool, BookCoreError> {
+        self.call(|core| Ok(core.has_explore()))
+    }
+
+    pub fn get_explore_sections(&self) -> Result<Vec<ExploreSection>, BookCoreError> {
+        self.call(|core| core.get_explore_sections())
+    }
+
+    pub fn get_explore(&self, section_id: String, page: u32) -> Result<SearchPage, BookCoreError> {
+        self.call(move |core| core.get_explore(section_id, page))
+    }
+
+    pub fn explore(&self, query: ExploreQuery) -> Result<SearchPage, BookCoreError> {
+        self.call(move |core| core.explore(query))
+    }
+
+    pub fn get_book_detail(&self, bid: String) -> Result<BookDetail, BookCoreError> {
+        self.call(move |core| core.get_book_detail(bid))
+    }
+
+    pub fn get_catalog(&self, bid: String) -> Result<Vec<CatalogVolume>, BookCoreError> {
+        self.call(move |core| core.get_catalog(bid))
+    }
+
+    pub fn get_latest_chapter(
+        &self,
+        bid: String,
+    ) -> Result<Option<BookLatestChapter>, BookCoreError> {
+        self.call(move |core| core.get_latest_chapter(bid))
+    }
+
+    pub fn get_catalog_page(&self, query: CatalogQuery) -> Result<CatalogPage, BookCoreError> {
+        self.call(move |core| core.get_catalog_page(query))
+    }
+
+    pub fn get_chapter(&self, bid: String, cid: String) -> Result<Chapter, BookCoreError> {
+        self.call(move |core| core.get_chapter(bid, cid))
+    }
+
+    pub fn has_comments(&self) -> Result<bool, BookCoreError> {
+        self.call(|core| Ok(core.has_comments()))
+    }
+
+    pub fn get_comments(&self, query: CommentQuery) -> Result<CommentPage, BookCoreError> {
+        self.call(move |core| core.get_comments(query))
+    }
+
+    pub fn has_shelf(&self) -> Result<bool, BookCoreError> {
+        self.call(|core| Ok(core.has_shelf()))
+    }
+
+    pub fn get_shelf(&self) -> Result<ShelfResult, BookCoreError> {
+        self.call(|core| core.get_shelf())
+    }
+
+    pub fn add_to_shelf(&self, bid: String) -> Result<ActionResult, BookCoreError> {
+        self.call(move |core| core.add_to_shelf(bid))
+    }
+
+    pub fn remove_from_shelf(&self, bid: String) -> Result<ActionResult, BookCoreError> {
+        self.call(move |core| core.remove_from_shelf(bid))
+    }
+
+    pub fn has_purchase(&self) -> Result<bool, BookCoreError> {
+        self.call(|core| Ok(core.has_purchase()))
+    }
+
+    pub fn get_chapter_prices(
+        &self,
+        bid: String,
+        cids: Vec<String>,
+    ) -> Result<BTreeMap<String, ChapterPrice>, BookCoreError> {
+        self.call(move |core| core.get_chapter_prices(bid, cids))
+    }
+
+    pub fn get_balance(&self) -> Result<Balance, BookCoreError> {
+        self.call(|core| core.get_balance())
+    }
+
+    pub fn quote_purchase(
+        &self,
+        bid: String,
+        cids: Vec<String>,
+    ) -> Result<PurchaseQuote, BookCoreError> {
+        self.call(move |core| core.quote_purchase(bid, cids))
+    }
+
+    pub fn confirm_purchase(&self, quote: PurchaseQuote) -> Result<PurchaseResult, BookCoreError> {
+        self.call(move |core| core.confirm_purchase(&quote))
+    }
+
+    pub async fn get_metadata_async(&self) -> Result<MetaData, BookCoreError> {
+        self.call_async(|core| core.get_metadata()).await
+    }
+
+    pub async fn get_forms_async(&self) -> Result<Vec<Form>, BookCoreError> {
+        self.call_async(|core| core.get_forms()).await
+    }
+
+    pub async fn get_actions_async(&self) -> Result<Vec<Action>, BookCoreError> {
+        self.call_async(|core| core.get_actions()).await
+    }
+
+    pub async fn run_action_async(&self, action: String) -> Result<Value, BookCoreError> {
+        self.call_async(move |core| core.run_action(action)).await
+    }
+
+    pub async fn submit_form_async(
+        &self,
+        form: String,
+        values: Map<String, Value>,
+    ) -> Result<FormOutcome, BookCoreError> {
+        self.call_async(move |core| core.submit_form(&form, values))
+            .await
+    }
+
+    pub async fn perform_action_async(
+        &self,
+        action: String,
+        values: Map<String, Value>,
+    ) -> Result<ActionResult, BookCoreError> {
+        self.call_async(move |core| core.perform_action(&action, values))
+            .await
+    }
+
+    pub async fn get_comments_async(
+        &self,
+        query: CommentQuery,
+    ) -> Result<CommentPage, BookCoreError> {
+        self.call_async(move |core| core.get_comments(query)).await
+    }
+
+    pub async fn start_qr_login_async(&self) -> Result<QrLogin, BookCoreError> {
+        self.call_async(|core| core.start_qr_login()).await
+    }
+
+    /// 与 `BookCore::wait_qr_login` 相同，但轮询间隔期间不占用工作线程
+    pub async fn wait_qr_login_async(
+        &self,
+        login: QrLogin,
+        token: CancellationToken,
+        on_status: impl FnMut(QrLoginStatus),
+    ) -> Result<QrLoginStatus, BookCoreError> {
+        let mut wait = QrLoginWait::new(&login, &token, on_status);
+        loop {
+            match wait.next()? {
+                QrLoginStep::Poll => {
+                    let poll = login.clone();
+                    let status = self
+                        .call_cancellable_async(&token, move |core| core.poll_qr_login(&poll))
+                        .await?;
+                    wait.record(status);
+                }
+                QrLoginStep::Sleep(duration) => sleep(duration).await,
+                QrLoginStep::Finished(status) => return Ok(status),
+            }
+        }
+    }
+
+    pub async fn search_books_async(
+        &self,
+        key: String,
+        page: u8,
+        count: u8,
+    ) -> Result<Vec<SearchBook>, BookCoreError> {
+        self.call_async(move |core| core.search_books(key, page, count))
+            .await
+    }
+
+    pub async fn search_async(&self, query: SearchQuery) -> Result<SearchPage, BookCoreError> {
+        self.call_async(move |core| core.search(query)).await
+    }
+
+    pub async fn get_explore_async(
+        &self,
+        section_id: String,
+        page: u32,
+    ) -> Result<SearchPage, BookCoreError> {
+        self.call_async(move |core| core.get_explore(section_id, page))
+            .await
+    }
+
+    pub async fn get_book_detail_async(&self, bid: String) -> Result<BookDetail, BookCoreError> {
+        self.call_async(move |core| core.get_book_detail(bid)).await
+    }
+
+    pub async fn get_catalog_async(
+        &self,
+        bid: String,
+    ) -> Result<Vec<CatalogVolume>, BookCoreError> {
+        self.call_async(move |core| core.get_catalog(bid)).await
+    }
+
+    pub async fn get_shelf_async(&self) -> Result<ShelfResult, BookCoreError> {
+        self.call_async(|core| core.get_shelf()).await
+    }
+
+    pub async fn add_to_shelf_async(&self, bid: String) -> Result<ActionResult, BookCoreError> {
+        self.call_async(move |core| core.add_to_shelf(bid)).await
+    }
+
+    pub async fn remove_from_shelf_async(
+        &self,
+        bid: String,
+    ) -> Result<ActionResult, BookCoreError> {
+        self.call_async(move |core| core.remove_from_shelf(bid))
+            .await
+    }
+
+    pub async fn get_balance_async(&self) -> Result<Balance, BookCoreError> {
+        self.call_async(|core| core.get_balance()).await
+    }
+
+    /// 与 `BookCore::buy` 相同，但 `confirm` 在调用方的任务中执行，等待用户确认时不占用工作线程
+    pub async fn buy_async(
+        &self,
+        bid: String,
+        cids: Vec<String>,
+        confirm: impl FnOnce(&PurchaseQuote) -> bool,
+    ) -> Result<PurchaseResult, BookCoreError> {
+        let quote = self
+            .call_async(move |core| core.quote_purchase(bid, cids))
+            .await?;
+        if let Some(result) = quote.precheck() {
+            return Ok(result);
+        }
+        if !confirm(&quote) {
+            return Ok(PurchaseResult::Declined);
+        }
+        self.call_async(move |core| core.confirm_purchase(&quote))
+            .await
+    }
+
+    pub async fn get_latest_chapter_async(
+        &self,
+        bid: String,
+    ) -> Result<Option<BookLatestChapter>, BookCoreError> {
+        self.call_async(move |core| core.get_latest_chapter(bid))
+            .await
+    }
+
+    pub async fn get_catalog_page_async(
+        &self,
+        query: CatalogQuery,
+    ) -> Result<CatalogPage, BookCoreError> {
+        self.call_async(move |core| core.get_catalog_page(query))
+            .await
+    }
+
+    /// 逐页获取目录，每获取一页调用一次 `on_page`，返回按 id 合并后的全部目录
+    ///
+    /// 每一页是一次单独的调用，页与页之间工作线程可以处理其他请求；页数上限同 `get_catalog`
+    pub async fn get_catalog_pages_async(
+        &self,
+        bid: String,
+        mut on_page: impl FnMut(&CatalogPage),
+    ) -> Result<Vec<CatalogVolume>, BookCoreError> {
+        let mut volumes = Vec::new();
+        let mut query = Some(CatalogQuery::new(bid));
+        let max_pages = self.call_async(|core| Ok(core.max_list_pages)).await?;
+        let mut paging = Paging::new(max_pages);
+        while let Some(current) = query {
+            paging.check(current.page)?;
+            let args = current.clone();
+            let page = self
+                .call_async(move |core| core.get_catalog_page(args))
+                .await?;
+            on_page(&page);
+            query = next_query(&current, &page, &mut paging);
+            merge_volumes(&mut volumes, page.volumes);
+        }
+        Ok(volumes)
+    }
+
+    pub async fn get_chapter_async(
+        &self,
+        bid: String,
+        cid: String,
+    ) -> Result<Chapter, BookCoreError> {
+        self.call_async(move |core| core.get_chapter(bid, cid))
+            .await
+    }
+
+    fn send(&self, job: Job) -> Result<(), BookCoreError> {
+        self.sender
+            .send(job)
+            .map_err(|_| BookCoreError::Disconnected)
+    }
+}
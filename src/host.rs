@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+use boa_engine::{
+    js_string, object::ObjectInitializer, property::Attribute, Context, JsError, JsNativeError,
+    JsResult, JsString, JsValue, NativeFunction,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{error::deserialize, json::to_json, BookCore, BookCoreError};
+
+type HostFn = Rc<dyn Fn(&[JsValue], &mut Context) -> JsResult<JsValue>>;
+
+/// 以命名空间对象的形式暴露给脚本的一组宿主函数
+///
+/// ```ignore
+/// let module = HostModule::new("app")
+///     .function("deviceId", |_: Vec<Value>| Ok("device"))
+///     .function("solveCaptcha", |(image,): (String,)| solve(image));
+/// core.register_module(module)?;
+/// // 脚本中：app.solveCaptcha(image)
+/// ```
+#[derive(Clone, Default)]
+pub struct HostModule {
+    name: String,
+    functions: Vec<(String, HostFn)>,
+}
+
+impl HostModule {
+    pub fn new(name: impl Into<String>) -> Self {
+        HostModule {
+            name: name.into(),
+            functions: Vec::new(),
+        }
+    }
+
+    /// 添加函数，参数与返回值的转换方式同 `BookCore::register_function`
+    pub fn function<A, R, F>(mut self, name: impl Into<String>, f: F) -> Self
+    where
+        A: DeserializeOwned,
+        R: Serialize,
+        F: Fn(A) -> Result<R, BookCoreError> + Send + 'static,
+    {
+        self.functions.push((name.into(), host_fn(f)));
+        self
+    }
+}
+
+impl BookCore {
+    /// 注册全局函数
+    ///
+    /// 脚本传入的参数会组成 JSON 数组后反序列化为 `A`，通常使用元组，例如 `(String, u32)`；
+    /// 返回值序列化后转换为 JS 值。返回 `Err` 时在脚本中抛出异常，未被捕获的异常原样返回给调用方。
+    /// `f` 需要是 `Send`，因此无法捕获 boa 的 JsValue、JsObject 等受 GC 管理的值
+    pub fn register_function<A, R, F>(&mut self, name: &str, f: F) -> Result<(), BookCoreError>
+    where
+        A: DeserializeOwned,
+        R: Serialize,
+        F: Fn(A) -> Result<R, BookCoreError> + Send + 'static,
+    {
+        let function = native_function(host_fn(f));
+        self.context
+            .register_global_callable(JsString::from(name), 0, function)
+            .map_err(|err| self.js_error(err))
+    }
+
+    /// 注册命名空间对象，模块内的函数作为该对象的方法暴露
+    pub fn register_module(&mut self, module: HostModule) -> Result<(), BookCoreError> {
+        let mut builder = ObjectInitializer::new(&mut self.context);
+        for (name, function) in module.functions {
+            builder.function(native_function(function), JsString::from(name.as_str()), 0);
+        }
+        let object = builder.build();
+        self.context
+            .register_global_property(
+                JsString::from(module.name.as_str()),
+                object,
+                Attribute::WRITABLE | Attribute::CONFIGURABLE,
+            )
+            .map_err(|err| self.js_error(err))
+    }
+}
+
+fn host_fn<A, R, F>(f: F) -> HostFn
+where
+    A: DeserializeOwned,
+    R: Serialize,
+    F: Fn(A) -> Result<R, BookCoreError> + Send + 'static,
+{
+    Rc::new(move |args, ctx| {
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args {
+            values.push(to_json(arg, ctx)?);
+        }
+        let args = deserialize(Value::Array(values)).map_err(|err| host_error(err, ctx))?;
+        let result = f(args).map_err(|err| host_error(err, ctx))?;
+        let result = serde_json::to_value(result).map_err(|err| {
+            JsNativeError::typ().with_message(format!("Failed to serialize result: {}", err))
+        })?;
+        JsValue::from_json(&result, ctx)
+    })
+}
+
+fn native_function(function: HostFn) -> NativeFunction {
+    // SAFETY: HostFn 只由 host_fn 构造，其中的用户闭包受 `Send` 约束，
+    // 而 boa 受 GC 管理的句柄（JsValue、JsObject 等）都不是 Send，因此闭包不会持有需要 GC 追踪的值
+    unsafe { NativeFunction::from_closure(move |_this, args, ctx| function(args, ctx)) }
+}
+
+/// 宿主函数返回的错误转为脚本异常，name 与 BookCoreError 的分类保持一致，便于原样传回
+fn host_error(err: BookCoreError, ctx: &mut Context) -> JsError {
+    let (name, message) = match err {
+        BookCoreError::Exception { name, message } => (name, message),
+        BookCoreError::Network(message) => ("NetworkError".to_string(), message),
+        err @ BookCoreError::Deserialize { .. } => ("TypeError".to_string(), err.to_string()),
+        err => ("Error".to_string(), err.to_string()),
+    };
+    let error = JsNativeError::error().with_message(message).to_opaque(ctx);
+    let _ = error.set(
+        js_string!("name"),
+        JsString::from(name.as_str()),
+        false,
+        ctx,
+    );
+    JsError::from_opaque(error.into())
+}
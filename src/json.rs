@@ -0,0 +1,33 @@
+use boa_engine::{js_string, Context, JsNativeError, JsResult, JsValue};
+use serde_json::Value;
+
+/// 将 JsValue 转为 serde_json::Value
+///
+/// boa 自带的 `to_json` 遇到 undefined 会直接 panic，这里借用 JSON.stringify 的规则：
+/// 对象中的 undefined 字段会被忽略，数组中的 undefined 会变成 null
+pub(crate) fn to_json(value: &JsValue, ctx: &mut Context) -> JsResult<Value> {
+    if value.is_undefined() {
+        return Ok(Value::Null);
+    }
+    let global = ctx.global_object();
+    let json = global.get(js_string!("JSON"), ctx)?;
+    let stringify = match json.as_object() {
+        Some(json) => json.get(js_string!("stringify"), ctx)?,
+        None => JsValue::undefined(),
+    };
+    let Some(stringify) = stringify.as_callable() else {
+        return Err(JsNativeError::typ()
+            .with_message("JSON.stringify is not callable")
+            .into());
+    };
+    let text = stringify.call(&json, &[value.clone()], ctx)?;
+    if text.is_undefined() {
+        return Ok(Value::Null);
+    }
+    let text = text.to_string(ctx)?.to_std_string_escaped();
+    serde_json::from_str(&text).map_err(|err| {
+        JsNativeError::typ()
+            .with_message(format!("Failed to parse JSON: {}", err))
+            .into()
+    })
+}
@@ -1,21 +1,77 @@
+mod action;
+mod builder;
+mod cancel;
+mod catalog;
+mod chapter;
+mod comment;
+mod content;
 mod crypto;
+mod diff;
 mod env;
+mod error;
+mod explore;
+mod form;
 mod global;
+mod handle;
+mod host;
+mod json;
+mod limits;
 mod prototype;
+mod purchase;
+mod qr_login;
+mod registry;
 mod request;
 mod runtime;
 mod scraper;
-use boa_engine::{js_string, Context, JsNativeError, JsResult, JsValue, Source};
+mod search;
+mod shelf;
+mod update;
+use boa_engine::{
+    builtins::promise::PromiseState, js_string, object::builtins::JsPromise, Context, JsError,
+    JsValue, Source,
+};
 use boa_runtime::{Console, Logger};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
-use tokio::runtime::Runtime;
-
-use crate::runtime::init_runtime;
+use std::time::Instant;
+use tokio::runtime::Handle;
+
+use crate::{error::deserialize, json::to_json, request::scope::RequestScope};
+
+pub use crate::{
+    action::{ActionResult, LoginRequired},
+    builder::BookCoreBuilder,
+    cancel::CancellationToken,
+    catalog::{merge_volumes, CatalogPage, CatalogPages, CatalogQuery},
+    comment::{Comment, CommentPage, CommentQuery},
+    content::{blocks_to_text, normalize_blocks, ContentBlock, TextSpan},
+    diff::{
+        diff_catalog, diff_catalog_with, CatalogDiff, ChapterChange, ChapterEntry,
+        DEFAULT_NAME_SIMILARITY,
+    },
+    error::BookCoreError,
+    explore::{ExploreQuery, ExploreSection, ExploreSectionType},
+    form::FormOutcome,
+    handle::BookCoreHandle,
+    host::HostModule,
+    limits::ExecutionLimits,
+    purchase::{Balance, ChapterPrice, PurchaseQuote, PurchaseResult},
+    qr_login::{QrLogin, QrLoginStatus},
+    registry::{SourceInfo, SourceRegistry},
+    runtime::Globals,
+    search::{FilterOption, FilterValue, SearchFilter, SearchFilterType, SearchPage, SearchQuery},
+    shelf::{ShelfBook, ShelfResult},
+    update::{UpdateCheck, UpdateStatus, UpdateTarget, DEFAULT_UPDATE_IN_FLIGHT},
+};
 
 #[derive(Debug)]
 pub struct BookCore {
     pub context: Context,
+    pub(crate) limits: ExecutionLimits,
+    pub(crate) cancel: Option<CancellationToken>,
+    pub(crate) running: bool,
+    pub(crate) max_chapter_pages: usize,
+    pub(crate) max_list_pages: usize,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -33,7 +89,7 @@ pub enum ProxyType {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Proxy {
     pub host: String,
-    pub port: u8,
+    pub port: u16,
     #[serde(rename = "proxyType")]
     pub proxy_type: Option<ProxyType>,
     pub username: Option<String>,
@@ -53,7 +109,7 @@ pub struct MetaData {
     pub version: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum FormFieldType {
     #[serde(rename = "input")]
     Input,
@@ -65,27 +121,62 @@ pub enum FormFieldType {
     Button,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FormFieldOption {
+    pub label: String,
+    pub value: String,
+}
+
+/// 字段的显示条件，`field` 的当前值等于 `equals` 或属于 `in` 时成立
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FormFieldCondition {
+    pub field: String,
+    pub equals: Option<Value>,
+    #[serde(rename = "in")]
+    pub one_of: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct FormField {
     #[serde(rename = "fieldType")]
     pub field_type: FormFieldType,
     pub field: String,
     pub label: String,
     pub placeholder: Option<String>,
+    /// 输入时隐藏内容，只影响展示，不影响取值是否保存
     pub password: Option<bool>,
+    /// 为 `false` 时取值只在表单处理函数执行期间写入 envs，适合不应长期保存的密码
+    pub persist: Option<bool>,
+    /// select 的可选项
+    pub options: Option<Vec<FormFieldOption>>,
+    pub default: Option<Value>,
+    pub required: Option<bool>,
+    /// input 取值需要完整匹配的正则
+    pub pattern: Option<String>,
+    #[serde(rename = "helpText")]
+    pub help_text: Option<String>,
+    /// 所有条件都成立时才显示该字段，隐藏的字段不会提交
+    #[serde(rename = "visibleWhen")]
+    pub visible_when: Option<Vec<FormFieldCondition>>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Form {
+    pub id: Option<String>,
     pub title: String,
     pub description: Option<String>,
     pub fields: Vec<FormField>,
+    /// 提交表单时调用的入口，未声明时使用表单中唯一的 button 字段
+    pub handler: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Action {
     pub label: String,
     pub action: String,
+    pub description: Option<String>,
+    /// 执行前需要用户填写的参数
+    pub form: Option<Form>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -113,7 +204,7 @@ pub struct SearchBook {
     pub lastest_chapter: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct BookLatestChapter {
     pub id: String,
     pub name: String,
@@ -146,7 +237,7 @@ pub struct BookDetail {
     pub extra_datas: Option<Vec<BookExtraData>>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CatalogChapter {
     pub id: String,
     pub name: String,
@@ -156,9 +247,11 @@ pub struct CatalogChapter {
     pub can_read: Option<bool>,
     #[serde(rename = "updateTime")]
     pub update_time: Option<String>,
+    /// VIP 章节的价格，用于在购买前展示
+    pub price: Option<ChapterPrice>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CatalogVolume {
     pub id: String,
     pub name: String,
@@ -168,7 +261,13 @@ pub struct CatalogVolume {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Chapter {
     pub id: String,
+    /// 纯文本正文，书源只返回 `blocks` 时由其生成
+    #[serde(default)]
     pub content: String,
+    /// 结构化正文，包含段落、标题、插图等
+    pub blocks: Option<Vec<ContentBlock>>,
+    /// 章节分页时下一页的令牌，`get_chapter` 会自动跟随，返回的章节中始终为空
+    pub continuation: Option<Value>,
     pub name: Option<String>,
     #[serde(rename = "isVip")]
     pub is_vip: Option<bool>,
@@ -185,133 +284,329 @@ pub struct Chapter {
 }
 
 impl BookCore {
-    pub fn init(code: String) -> Self {
-        let mut core = Self {
-            context: Context::default(),
-        };
-        init_runtime(&mut core);
-        core.context
-            .eval(Source::from_bytes(format!("setEnvs()").as_bytes()))
-            .expect("Failed to eval __ENVS__");
-        core.context
-            .eval(Source::from_bytes(code.as_str()))
-            .unwrap();
-        core
+    pub fn init(code: String) -> Result<Self, BookCoreError> {
+        Self::builder().build(code)
+    }
+
+    pub fn builder() -> BookCoreBuilder {
+        BookCoreBuilder::new()
     }
 
-    pub fn regist_cust_logger(&mut self, logger: impl Logger + 'static) {
+    pub fn regist_cust_logger(
+        &mut self,
+        logger: impl Logger + 'static,
+    ) -> Result<(), BookCoreError> {
         let context = &mut self.context;
         context
             .global_object()
             .delete_property_or_throw(js_string!("console"), context)
-            .expect("Failed to delete console");
-        Console::register_with_logger(context, logger).expect("Failed to register custom logger");
+            .map_err(|err| BookCoreError::from_js(err, context))?;
+        Console::register_with_logger(context, logger)
+            .map_err(|err| BookCoreError::from_js(err, context))?;
         context
         .eval(Source::from_bytes("const console_log = console.log;console.log = function(...args) { const string_args =  args.map(arg => typeof arg === 'object' ? JSON.stringify(arg) : arg); console_log(...string_args);  };"))
-        .expect("Failed to eval console");
+        .map_err(|err| BookCoreError::from_js(err, context))?;
+        Ok(())
     }
 
-    pub fn eval<T>(&mut self, code: String) -> Result<T, String>
+    pub fn eval<T>(&mut self, code: String) -> Result<T, BookCoreError>
     where
         T: DeserializeOwned,
     {
-        let rt = Runtime::new().unwrap();
-        let code = format!("{}", code);
-        rt.block_on(async {
-            let ctx = &mut self.context;
-            ctx.eval(Source::from_bytes(code.as_bytes()))
-                .map(|value| {
-                    if value.is_null_or_undefined() {
-                        serde_json::from_value::<T>(serde_json::Value::Null).unwrap()
-                    } else {
-                        let value = value.to_json(ctx).unwrap();
-                        serde_json::from_value::<T>(value).unwrap()
-                    }
-                })
-                .map_err(|err| err.to_string())
+        self.run(|core| {
+            let value = core
+                .context
+                .eval(Source::from_bytes(code.as_bytes()))
+                .map_err(|err| core.js_error(err))?;
+            let value = core.settle(value)?;
+            core.to_value(value)
         })
     }
 
-    pub fn call_func(&mut self, func: String, args: Vec<Value>) -> JsResult<JsValue> {
-        let context = &mut self.context;
-        let global = context.global_object();
-        let func = global.get(js_string!(func), context)?;
-        if !func.is_callable() {
-            Err(JsNativeError::typ()
-                .with_message(format!("{:?} is not callable", func))
-                .into())
-        } else {
-            let func = func.as_callable().unwrap();
-            let args: Vec<JsValue> = args
-                .into_iter()
-                .map(|arg| JsValue::from_json(&arg, context).unwrap())
-                .collect();
-            func.call(&JsValue::undefined(), &args, context)
+    /// 指定 JReqwest 发起网络请求所用的 tokio 运行时，未指定时使用共享运行时
+    ///
+    /// 脚本线程会等待请求完成，所以该运行时必须由其他线程驱动
+    pub fn set_runtime(&mut self, handle: Handle) {
+        RequestScope {
+            handle,
+            ..RequestScope::current(&self.context)
+        }
+        .install(&self.context);
+    }
+
+    /// 设置之后每次调用的执行预算
+    pub fn set_limits(&mut self, limits: ExecutionLimits) {
+        self.limits = limits;
+    }
+
+    pub fn limits(&self) -> ExecutionLimits {
+        self.limits
+    }
+
+    /// 在 `f` 内的调用上叠加 `limits` 中已设置的项，结束后恢复原有预算
+    pub fn with_limits<T>(
+        &mut self,
+        limits: ExecutionLimits,
+        f: impl FnOnce(&mut Self) -> Result<T, BookCoreError>,
+    ) -> Result<T, BookCoreError> {
+        let previous = self.limits;
+        self.limits = previous.merge(limits);
+        let result = f(self);
+        self.limits = previous;
+        result
+    }
+
+    /// 在 `f` 内的调用上绑定取消令牌，令牌触发后调用返回 `BookCoreError::Cancelled`
+    pub fn with_cancellation<T>(
+        &mut self,
+        token: &CancellationToken,
+        f: impl FnOnce(&mut Self) -> Result<T, BookCoreError>,
+    ) -> Result<T, BookCoreError> {
+        let previous = self.cancel.replace(token.clone());
+        let result = f(self);
+        self.cancel = previous;
+        result
+    }
+
+    pub fn call_func(&mut self, func: String, args: Vec<Value>) -> Result<JsValue, BookCoreError> {
+        self.run(|core| core.call(&func, args))
+    }
+
+    fn call(&mut self, func: &str, args: Vec<Value>) -> Result<JsValue, BookCoreError> {
+        let func_value = self.lookup(func)?;
+        let Some(func_obj) = func_value.as_callable() else {
+            return Err(BookCoreError::Exception {
+                name: "TypeError".to_string(),
+                message: format!("{} is not callable", func),
+            });
+        };
+        let mut js_args = Vec::with_capacity(args.len());
+        for arg in args {
+            let arg =
+                JsValue::from_json(&arg, &mut self.context).map_err(|err| self.js_error(err))?;
+            js_args.push(arg);
         }
+        let value = func_obj
+            .call(&JsValue::undefined(), &js_args, &mut self.context)
+            .map_err(|err| self.js_error(err))?;
+        self.settle(value)
     }
 
-    pub fn set_envs(&mut self, envs: Value) -> Result<(), String> {
+    pub fn set_envs(&mut self, envs: Value) -> Result<(), BookCoreError> {
         self.call_func("setEnvs".to_string(), vec![envs])
             .map(|_| ())
-            .map_err(|err| err.to_string())
     }
 
-    pub fn set_env(&mut self, key: String, value: Value) -> Result<Value, String> {
-        self.eval::<Value>(format!("setEnv('{}', {:?})", key, value))
+    pub fn set_env(&mut self, key: String, value: Value) -> Result<Value, BookCoreError> {
+        let value = self.call_func("setEnv".to_string(), vec![json!(key), value])?;
+        self.to_value(value)
     }
 
-    pub fn get_envs(&mut self) -> Result<Value, String> {
-        self.eval::<Value>(format!("getEnvs()"))
+    pub fn get_envs(&mut self) -> Result<Value, BookCoreError> {
+        self.invoke("getEnvs", Value::Null)
     }
 
-    pub fn get_env(&mut self, key: String) -> Result<Value, String> {
-        self.call_func("getEnv".to_string(), vec![json!(key)])
-            .map(|value| value.to_json(&mut self.context).unwrap())
-            .map_err(|err| err.to_string())
+    pub fn get_env(&mut self, key: String) -> Result<Value, BookCoreError> {
+        let value = self.call_func("getEnv".to_string(), vec![json!(key)])?;
+        self.to_value(value)
     }
 
-    pub fn clear_envs(&mut self) {
-        self.call_func("clearEnvs".to_string(), vec![])
-            .expect("Failed to clear envs");
+    pub fn clear_envs(&mut self) -> Result<(), BookCoreError> {
+        self.call_func("clearEnvs".to_string(), vec![]).map(|_| ())
     }
 
-    pub fn get_metadata(&mut self) -> Result<MetaData, String> {
-        self.eval::<MetaData>("metadata".to_string())
+    pub fn get_metadata(&mut self) -> Result<MetaData, BookCoreError> {
+        self.read("metadata")
     }
 
-    pub fn get_forms(&mut self) -> Result<Vec<Form>, String> {
-        self.eval::<Vec<Form>>("forms".to_string())
+    pub fn get_forms(&mut self) -> Result<Vec<Form>, BookCoreError> {
+        self.read("forms")
     }
 
-    pub fn get_actions(&mut self) -> Result<Vec<Action>, String> {
-        self.eval::<Vec<Action>>("actions".to_string())
+    pub fn get_actions(&mut self) -> Result<Vec<Action>, BookCoreError> {
+        self.read("actions")
     }
 
-    pub fn run_action(&mut self, action: String) -> Result<Value, String> {
-        self.eval(format!("{}()", action).to_string())
+    /// 直接调用动作入口并返回原始结果，需要参数或类型化结果时使用 `perform_action`
+    pub fn run_action(&mut self, action: String) -> Result<Value, BookCoreError> {
+        self.invoke(&action, Value::Null)
     }
 
+    /// 只取一页结果的旧接口，需要分页信息时使用 `search`
     pub fn search_books(
         &mut self,
         key: String,
         page: u8,
         count: u8,
-    ) -> Result<Vec<SearchBook>, String> {
-        self.eval::<Vec<SearchBook>>(format!(
-            "search({{key: '{}', page: {}, count: {}}});",
-            key, page, count
-        ))
+    ) -> Result<Vec<SearchBook>, BookCoreError> {
+        let query = SearchQuery::new(key).page(page.into()).count(count.into());
+        self.search(query).map(|page| page.items)
     }
 
-    pub fn get_book_detail(&mut self, bid: String) -> Result<BookDetail, String> {
-        self.eval::<BookDetail>(format!("detail({{bid: '{}'}});", bid))
+    pub fn get_book_detail(&mut self, bid: String) -> Result<BookDetail, BookCoreError> {
+        self.invoke("detail", json!({ "bid": bid }))
     }
 
-    pub fn get_catalog(&mut self, bid: String) -> Result<Vec<CatalogVolume>, String> {
-        self.eval::<Vec<CatalogVolume>>(format!("catalog({{bid: '{}'}});", bid))
+    /// 获取章节，分页拼接在同一次执行预算内完成，超时从第一页开始计算
+    pub fn get_chapter(&mut self, bid: String, cid: String) -> Result<Chapter, BookCoreError> {
+        self.run(|core| {
+            let mut chapter = core.fetch_chapter(&bid, &cid)?;
+            if chapter.blocks.is_some() {
+                let metadata: Option<MetaData> = core.read_optional("metadata")?;
+                chapter.normalize(metadata.as_ref().map(|metadata| metadata.base_url.as_str()))?;
+            }
+            Ok(chapter)
+        })
+    }
+
+    /// 调用书源中的任意入口函数
+    ///
+    /// `args` 会通过 `JsValue::from_json` 作为唯一参数传入，为 `null` 时不传参数；
+    /// 参数不会拼接进脚本源码，因此任意内容都不会改变调用本身
+    pub fn invoke<T: DeserializeOwned>(
+        &mut self,
+        entry: &str,
+        args: Value,
+    ) -> Result<T, BookCoreError> {
+        let args = if args.is_null() { vec![] } else { vec![args] };
+        let value = self.call_func(entry.to_string(), args)?;
+        self.to_value(value)
+    }
+
+    /// 查找书源中定义的入口，`const`/`let` 声明的入口不在 global object 上，需要通过求值获取
+    fn lookup(&mut self, name: &str) -> Result<JsValue, BookCoreError> {
+        if !is_identifier(name) {
+            return Err(BookCoreError::MissingEntry(name.to_string()));
+        }
+        let code = format!("typeof {0} === 'undefined' ? undefined : {0}", name);
+        let value = self
+            .context
+            .eval(Source::from_bytes(code.as_bytes()))
+            .map_err(|err| self.js_error(err))?;
+        if value.is_undefined() {
+            Err(BookCoreError::MissingEntry(name.to_string()))
+        } else {
+            Ok(value)
+        }
     }
 
-    pub fn get_chapter(&mut self, bid: String, cid: String) -> Result<Chapter, String> {
-        self.eval::<Chapter>(format!("chapter({{bid: '{}', cid: '{}'}});", bid, cid))
+    /// 读取书源中声明的常量，例如 metadata、forms
+    fn read<T: DeserializeOwned>(&mut self, name: &str) -> Result<T, BookCoreError> {
+        self.run(|core| {
+            let value = core.lookup(name)?;
+            let value = core.settle(value)?;
+            core.to_value(value)
+        })
+    }
+
+    /// 书源是否声明了名为 `name` 的入口或常量
+    pub fn has_entry(&mut self, name: &str) -> bool {
+        self.lookup(name).is_ok()
+    }
+
+    /// 读取可选的常量，书源未声明时返回 `None`
+    pub(crate) fn read_optional<T: DeserializeOwned>(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<T>, BookCoreError> {
+        match self.read(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(BookCoreError::MissingEntry(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// 检查截止时间与取消，供跨多次调用的流程在两次调用之间使用
+    pub(crate) fn checkpoint(&mut self) -> Result<(), BookCoreError> {
+        RequestScope::current(&self.context)
+            .check()
+            .map_err(|err| self.js_error(err))
+    }
+
+    /// 在执行预算和取消令牌下运行一次调用，嵌套调用沿用最外层的设置
+    pub(crate) fn run<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, BookCoreError>,
+    ) -> Result<T, BookCoreError> {
+        if self.running {
+            return f(self);
+        }
+        let cancel = self.cancel.clone();
+        if cancel.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(BookCoreError::Cancelled);
+        }
+        let deadline = self.limits.timeout.map(|timeout| Instant::now() + timeout);
+        let loop_budget = self.limits.timeout_loop_budget();
+        let previous = RequestScope::current(&self.context);
+        RequestScope {
+            deadline,
+            cancel: cancel.clone(),
+            ..previous.clone()
+        }
+        .install(&self.context);
+        let runtime_limits = self.context.runtime_limits();
+        self.context
+            .set_runtime_limits(self.limits.runtime_limits());
+        self.running = true;
+        let result = f(self);
+        self.running = false;
+        self.context.set_runtime_limits(runtime_limits);
+        previous.install(&self.context);
+        match result {
+            Err(BookCoreError::LimitExceeded(_) | BookCoreError::PromisePending)
+                if cancel.as_ref().is_some_and(CancellationToken::is_cancelled) =>
+            {
+                Err(BookCoreError::Cancelled)
+            }
+            Err(BookCoreError::LimitExceeded(_) | BookCoreError::PromisePending)
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) =>
+            {
+                Err(BookCoreError::Timeout)
+            }
+            // 由超时折算的循环上限用尽，视为超时
+            Err(BookCoreError::LimitExceeded(message))
+                if loop_budget.is_some() && message.contains("loop iteration") =>
+            {
+                Err(BookCoreError::Timeout)
+            }
+            result => result,
+        }
+    }
+
+    /// 返回值为 Promise 时驱动任务队列直到其完成，rejected 的 Promise 转为错误
+    fn settle(&mut self, value: JsValue) -> Result<JsValue, BookCoreError> {
+        let Some(promise) = value
+            .as_object()
+            .and_then(|obj| JsPromise::from_object(obj.clone()).ok())
+        else {
+            return Ok(value);
+        };
+        self.context.run_jobs();
+        match promise.state() {
+            PromiseState::Fulfilled(value) => Ok(value),
+            PromiseState::Rejected(reason) => Err(self.js_error(JsError::from_opaque(reason))),
+            PromiseState::Pending => Err(BookCoreError::PromisePending),
+        }
+    }
+
+    pub(crate) fn to_value<T: DeserializeOwned>(
+        &mut self,
+        value: JsValue,
+    ) -> Result<T, BookCoreError> {
+        let value = to_json(&value, &mut self.context).map_err(|err| self.js_error(err))?;
+        deserialize(value)
+    }
+
+    pub(crate) fn js_error(&mut self, err: JsError) -> BookCoreError {
+        BookCoreError::from_js(err, &mut self.context)
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$' => {}
+        _ => return false,
     }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
 }
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use boa_engine::vm::RuntimeLimits;
+
+/// 只设置了超时时，每毫秒超时折算的循环次数
+const LOOP_ITERATIONS_PER_MS: u64 = 5_000;
+
+/// 脚本执行预算，未设置的项不做限制
+///
+/// 循环次数与递归深度交给 boa 的 RuntimeLimits 处理；超时在 JReqwest 等原生函数的检查点上生效。
+/// 只设置了超时时，单个循环的迭代次数按超时折算出上限，用尽后同样返回 `BookCoreError::Timeout`，
+/// 因此纯 JS 的死循环也会停止，但停止的时间取决于脚本的执行速度，并不保证在超时内返回
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// 单个函数帧内循环的最大迭代次数
+    pub loop_iterations: Option<u64>,
+    /// 最大递归深度
+    pub recursion: Option<usize>,
+    /// 单次调用的最长耗时，包含 JReqwest 等待响应的时间
+    ///
+    /// 只在原生函数的检查点上按墙钟时间生效。对纯 JS 代码这不是墙钟时间的保证，
+    /// 而是每毫秒 5,000 次的循环预算（200ms 即单个循环最多 100 万次），
+    /// 循环体较重或在 debug 构建中运行时，实际耗时可能远超超时
+    pub timeout: Option<Duration>,
+}
+
+impl ExecutionLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn loop_iterations(mut self, limit: u64) -> Self {
+        self.loop_iterations = Some(limit);
+        self
+    }
+
+    pub fn recursion(mut self, limit: usize) -> Self {
+        self.recursion = Some(limit);
+        self
+    }
+
+    /// 设置超时；对纯 JS 代码这是循环预算，不是墙钟时间的保证，见 `timeout` 字段
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 用 `other` 中已设置的项覆盖当前值
+    pub fn merge(self, other: ExecutionLimits) -> Self {
+        ExecutionLimits {
+            loop_iterations: other.loop_iterations.or(self.loop_iterations),
+            recursion: other.recursion.or(self.recursion),
+            timeout: other.timeout.or(self.timeout),
+        }
+    }
+
+    /// 只设置了超时时由超时折算的循环次数上限
+    pub(crate) fn timeout_loop_budget(&self) -> Option<u64> {
+        match (self.loop_iterations, self.timeout) {
+            (None, Some(timeout)) => {
+                let millis = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+                Some(millis.max(1).saturating_mul(LOOP_ITERATIONS_PER_MS))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn runtime_limits(&self) -> RuntimeLimits {
+        let mut limits = RuntimeLimits::default();
+        if let Some(loop_iterations) = self.loop_iterations.or(self.timeout_loop_budget()) {
+            limits.set_loop_iteration_limit(loop_iterations);
+        }
+        if let Some(recursion) = self.recursion {
+            limits.set_recursion_limit(recursion);
+        }
+        limits
+    }
+}
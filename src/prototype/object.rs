@@ -2,7 +2,7 @@ use boa_engine::{
     js_string,
     object::FunctionObjectBuilder,
     property::{PropertyDescriptor, PropertyKey},
-    Context, JsError, JsString, JsValue, NativeFunction,
+    Context, JsError, JsValue, NativeFunction,
 };
 
 fn register(ctx: &mut Context, name: &str, func: NativeFunction) -> Result<bool, JsError> {
@@ -24,18 +24,16 @@ fn regist_to_query(ctx: &mut Context) -> Result<bool, JsError> {
         let this_obj = this.to_object(context)?;
         let mut result = String::new();
         let keys = this_obj.own_property_keys(context)?;
-        keys.iter().for_each(|key| {
+        for key in keys {
             let key_str = key.to_string();
-            let value = this_obj
-                .get::<JsString>(js_string!(key_str.clone()), context)
-                .unwrap();
-            let value_str = value.to_string(context).unwrap();
+            let value = this_obj.get(key, context)?;
+            let value_str = value.to_string(context)?;
             result.push_str(&format!(
                 "{}={}&",
                 key_str,
                 value_str.to_std_string_escaped()
             ));
-        });
+        }
         result.pop();
         Ok(JsValue::String(js_string!(result)))
     });
@@ -76,7 +76,7 @@ fn register_to_ascii(context: &mut Context) -> Result<bool, JsError> {
 fn register_to_sha(context: &mut Context) -> Result<bool, JsError> {
     let func = NativeFunction::from_fn_ptr(|this, args, context| {
         let this_str = this.to_string(context)?.to_std_string_escaped();
-        let hash = args.get(0).unwrap().to_string(context)?.to_std_string_escaped();
+        let hash = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
         let to_string = args.get_or_undefined(1);
         // let mut hasher = Sha256::new();
         // hasher.update(this_str);
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{error::deserialize, BookCore, BookCoreError, LoginRequired};
+
+/// 章节价格，`amount` 以站点货币的最小单位计，例如书币
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ChapterPrice {
+    pub amount: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+}
+
+/// 账号余额，单位与 `ChapterPrice` 相同
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Balance {
+    pub amount: u64,
+    pub currency: Option<String>,
+}
+
+/// 购买前交给宿主确认的报价
+#[derive(Debug, Clone, PartialEq)]
+pub struct PurchaseQuote {
+    pub bid: String,
+    pub cids: Vec<String>,
+    /// 书源声明了 `price` 时的各章价格
+    pub prices: BTreeMap<String, ChapterPrice>,
+    /// 书源声明了 `price` 时的总价，没有返回价格的章节按免费计
+    pub total: Option<u64>,
+    /// 书源声明了 `balance` 时的当前余额
+    pub balance: Option<Balance>,
+    /// `price` 或 `balance` 要求先登录，此时价格与余额都为空
+    pub login_required: Option<LoginRequired>,
+}
+
+impl PurchaseQuote {
+    /// 无需确认即可得出的结果：需要先登录，或总价与余额都已知且余额不足
+    pub(crate) fn precheck(&self) -> Option<PurchaseResult> {
+        if let Some(login) = &self.login_required {
+            return Some(PurchaseResult::LoginRequired(login.clone()));
+        }
+        let total = self.total?;
+        let balance = self.balance.as_ref()?.amount;
+        (total > balance).then_some(PurchaseResult::InsufficientBalance {
+            required: Some(total),
+            balance: Some(balance),
+        })
+    }
+}
+
+/// 书源 `buy` 的结果
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type")]
+pub enum PurchaseResult {
+    /// 购买成功，`balance` 为剩余余额
+    #[serde(rename = "bought")]
+    Bought { balance: Option<u64> },
+    #[serde(rename = "insufficientBalance")]
+    InsufficientBalance {
+        required: Option<u64>,
+        balance: Option<u64>,
+    },
+    /// 章节已购买，无需重复扣费
+    #[serde(rename = "alreadyOwned")]
+    AlreadyOwned,
+    #[serde(rename = "loginRequired")]
+    LoginRequired(LoginRequired),
+    /// 宿主在确认步骤中拒绝了购买，没有调用书源
+    #[serde(skip)]
+    Declined,
+}
+
+impl BookCore {
+    /// 书源是否支持购买章节
+    pub fn has_purchase(&mut self) -> bool {
+        self.has_entry("buy")
+    }
+
+    /// 查询章节价格，书源的 `price` 以 `{ bid, cids }` 调用，返回以章节 id 为键的价格
+    ///
+    /// 免费或已购买的章节可以不返回
+    pub fn get_chapter_prices(
+        &mut self,
+        bid: String,
+        cids: Vec<String>,
+    ) -> Result<BTreeMap<String, ChapterPrice>, BookCoreError> {
+        self.invoke("price", json!({ "bid": bid, "cids": cids }))
+    }
+
+    /// 查询账号余额，书源的 `balance` 返回 `{ amount, currency }`
+    pub fn get_balance(&mut self) -> Result<Balance, BookCoreError> {
+        self.invoke("balance", Value::Null)
+    }
+
+    /// 生成购买报价，书源没有声明 `price` 或 `balance` 时对应字段为空
+    ///
+    /// `price` 或 `balance` 返回 `{ type: 'loginRequired' }` 时记录在 `login_required` 中，
+    /// 不再继续查询
+    pub fn quote_purchase(
+        &mut self,
+        bid: String,
+        cids: Vec<String>,
+    ) -> Result<PurchaseQuote, BookCoreError> {
+        let mut quote = PurchaseQuote {
+            bid,
+            cids,
+            prices: BTreeMap::new(),
+            total: None,
+            balance: None,
+            login_required: None,
+        };
+        if self.has_entry("price") {
+            let value: Value =
+                self.invoke("price", json!({ "bid": quote.bid, "cids": quote.cids }))?;
+            if let Some(login) = LoginRequired::from_value(&value)? {
+                quote.login_required = Some(login);
+                return Ok(quote);
+            }
+            quote.prices = deserialize(value)?;
+            quote.total = Some(
+                quote
+                    .cids
+                    .iter()
+                    .filter_map(|cid| quote.prices.get(cid))
+                    .map(|price| price.amount)
+                    .sum(),
+            );
+        }
+        if self.has_entry("balance") {
+            let value: Value = self.invoke("balance", Value::Null)?;
+            if let Some(login) = LoginRequired::from_value(&value)? {
+                quote.login_required = Some(login);
+                return Ok(quote);
+            }
+            quote.balance = Some(deserialize(value)?);
+        }
+        Ok(quote)
+    }
+
+    /// 购买章节
+    ///
+    /// 先生成报价，需要登录或已知余额不足时直接返回 `LoginRequired`/`InsufficientBalance`；
+    /// 否则交给 `confirm` 确认，确认后以 `{ bid, cids }` 调用书源的 `buy`
+    pub fn buy(
+        &mut self,
+        bid: String,
+        cids: Vec<String>,
+        confirm: impl FnOnce(&PurchaseQuote) -> bool,
+    ) -> Result<PurchaseResult, BookCoreError> {
+        let quote = self.quote_purchase(bid, cids)?;
+        if let Some(result) = quote.precheck() {
+            return Ok(result);
+        }
+        if !confirm(&quote) {
+            return Ok(PurchaseResult::Declined);
+        }
+        self.confirm_purchase(&quote)
+    }
+
+    /// 按已确认的报价调用书源的 `buy`，跳过确认步骤
+    pub fn confirm_purchase(
+        &mut self,
+        quote: &PurchaseQuote,
+    ) -> Result<PurchaseResult, BookCoreError> {
+        let value: Value = self.invoke("buy", json!({ "bid": quote.bid, "cids": quote.cids }))?;
+        if value.is_null() || value == Value::Bool(true) {
+            return Ok(PurchaseResult::Bought { balance: None });
+        }
+        deserialize(value)
+    }
+}
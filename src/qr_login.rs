@@ -0,0 +1,226 @@
+use std::{
+    io::Cursor,
+    thread,
+    time::{Duration, Instant},
+};
+
+use image::{ImageFormat, Luma};
+use qrcode::{render::svg, QrCode};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{error::deserialize, BookCore, BookCoreError, CancellationToken};
+
+/// 轮询之间等待时检查取消状态的间隔
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 两次轮询之间的最短间隔，避免书源返回 `interval: 0` 时不停地请求
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 书源 `qrLogin` 返回的二维码
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrLogin {
+    /// 二维码内容，通常是一个 URL
+    pub payload: String,
+    /// 原样传给 `qrLoginStatus` 的轮询句柄
+    pub handle: Value,
+    /// 两次轮询之间的间隔，等待时最短按 100 毫秒处理
+    pub interval: Duration,
+    pub expires_at: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum QrLoginStatus {
+    #[serde(rename = "waiting")]
+    Waiting,
+    /// 已扫码，等待在手机上确认
+    #[serde(rename = "scanned")]
+    Scanned,
+    /// 已确认，凭据已写入 envs
+    #[serde(rename = "confirmed")]
+    Confirmed,
+    #[serde(rename = "expired")]
+    Expired,
+}
+
+impl QrLoginStatus {
+    /// 是否不需要继续轮询
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Confirmed | Self::Expired)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawQrLogin {
+    payload: String,
+    #[serde(default)]
+    handle: Value,
+    /// 毫秒
+    interval: Option<u64>,
+    /// 秒
+    #[serde(rename = "expiresIn")]
+    expires_in: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawQrLoginStatus {
+    status: QrLoginStatus,
+    envs: Option<Map<String, Value>>,
+}
+
+impl QrLogin {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| Instant::now() >= expires_at)
+    }
+
+    pub fn to_svg(&self) -> Result<String, BookCoreError> {
+        Ok(self
+            .code()?
+            .render::<svg::Color>()
+            .min_dimensions(200, 200)
+            .build())
+    }
+
+    /// 渲染为 PNG，`size` 为最小边长（像素）
+    pub fn to_png(&self, size: u32) -> Result<Vec<u8>, BookCoreError> {
+        let image = self
+            .code()?
+            .render::<Luma<u8>>()
+            .min_dimensions(size, size)
+            .build();
+        let mut png = Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, ImageFormat::Png)
+            .map_err(|err| BookCoreError::QrCode(err.to_string()))?;
+        Ok(png.into_inner())
+    }
+
+    fn code(&self) -> Result<QrCode, BookCoreError> {
+        QrCode::new(self.payload.as_bytes()).map_err(|err| BookCoreError::QrCode(err.to_string()))
+    }
+}
+
+impl BookCore {
+    /// 书源是否支持扫码登录，需要同时声明 `qrLogin` 与 `qrLoginStatus`
+    pub fn has_qr_login(&mut self) -> bool {
+        self.has_entry("qrLogin") && self.has_entry("qrLoginStatus")
+    }
+
+    /// 调用书源的 `qrLogin` 获取二维码
+    ///
+    /// 书源返回 `{ payload, handle, interval, expiresIn }`，interval 单位为毫秒，默认 2 秒，
+    /// 最短 100 毫秒；expiresIn 单位为秒，大到无法表示时视为不过期
+    pub fn start_qr_login(&mut self) -> Result<QrLogin, BookCoreError> {
+        let raw: RawQrLogin = deserialize(self.invoke("qrLogin", Value::Null)?)?;
+        Ok(QrLogin {
+            payload: raw.payload,
+            handle: raw.handle,
+            interval: Duration::from_millis(raw.interval.unwrap_or(2000)).max(MIN_POLL_INTERVAL),
+            expires_at: raw
+                .expires_in
+                .and_then(|expires_in| Instant::now().checked_add(Duration::from_secs(expires_in))),
+        })
+    }
+
+    /// 轮询一次扫码状态
+    ///
+    /// 书源的 `qrLoginStatus(handle)` 返回 `{ status, envs }`，确认后 envs 会写入 BookCore；
+    /// 二维码过期后不再调用书源
+    pub fn poll_qr_login(&mut self, login: &QrLogin) -> Result<QrLoginStatus, BookCoreError> {
+        if login.is_expired() {
+            return Ok(QrLoginStatus::Expired);
+        }
+        let value = self.call_func("qrLoginStatus".to_string(), vec![login.handle.clone()])?;
+        let raw: RawQrLoginStatus = self.to_value(value)?;
+        if raw.status == QrLoginStatus::Confirmed {
+            for (key, value) in raw.envs.unwrap_or_default() {
+                self.set_env(key, value)?;
+            }
+        }
+        Ok(raw.status)
+    }
+
+    /// 按间隔轮询直到确认或过期，每次状态变化时调用 `on_status`
+    ///
+    /// 会阻塞当前线程，`token` 取消后返回 `BookCoreError::Cancelled`
+    pub fn wait_qr_login(
+        &mut self,
+        login: &QrLogin,
+        token: &CancellationToken,
+        on_status: impl FnMut(QrLoginStatus),
+    ) -> Result<QrLoginStatus, BookCoreError> {
+        let mut wait = QrLoginWait::new(login, token, on_status);
+        loop {
+            match wait.next()? {
+                QrLoginStep::Poll => {
+                    let status = self.with_cancellation(token, |core| core.poll_qr_login(login))?;
+                    wait.record(status);
+                }
+                QrLoginStep::Sleep(duration) => thread::sleep(duration),
+                QrLoginStep::Finished(status) => return Ok(status),
+            }
+        }
+    }
+}
+
+/// 等待扫码登录时的下一步
+pub(crate) enum QrLoginStep {
+    /// 调用书源轮询一次，结果交给 `QrLoginWait::record`
+    Poll,
+    Sleep(Duration),
+    Finished(QrLoginStatus),
+}
+
+/// 轮询扫码状态的共同逻辑：去重回调状态、按间隔等待并检查取消，
+/// 同步与异步的 `wait_qr_login` 只负责调用书源与睡眠
+pub(crate) struct QrLoginWait<'a, F> {
+    login: &'a QrLogin,
+    token: &'a CancellationToken,
+    on_status: F,
+    last: Option<QrLoginStatus>,
+    /// 上次轮询的时间，为空时立即轮询
+    polled_at: Option<Instant>,
+}
+
+impl<'a, F: FnMut(QrLoginStatus)> QrLoginWait<'a, F> {
+    pub(crate) fn new(login: &'a QrLogin, token: &'a CancellationToken, on_status: F) -> Self {
+        QrLoginWait {
+            login,
+            token,
+            on_status,
+            last: None,
+            polled_at: None,
+        }
+    }
+
+    pub(crate) fn next(&mut self) -> Result<QrLoginStep, BookCoreError> {
+        if let Some(status) = self.last.filter(QrLoginStatus::is_finished) {
+            return Ok(QrLoginStep::Finished(status));
+        }
+        if self.token.is_cancelled() {
+            return Err(BookCoreError::Cancelled);
+        }
+        let Some(polled_at) = self.polled_at else {
+            return Ok(QrLoginStep::Poll);
+        };
+        // 只比较已经过去的时间，interval 再大也不会溢出；过期后立即轮询，由 poll_qr_login 返回 Expired
+        let left = self
+            .login
+            .interval
+            .max(MIN_POLL_INTERVAL)
+            .saturating_sub(polled_at.elapsed());
+        if left.is_zero() || self.login.is_expired() {
+            return Ok(QrLoginStep::Poll);
+        }
+        Ok(QrLoginStep::Sleep(CANCEL_CHECK_INTERVAL.min(left)))
+    }
+
+    pub(crate) fn record(&mut self, status: QrLoginStatus) {
+        if self.last != Some(status) {
+            (self.on_status)(status);
+            self.last = Some(status);
+        }
+        self.polled_at = Some(Instant::now());
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+};
+
+use uuid::Uuid;
+
+use crate::{BookCore, BookCoreError, BookCoreHandle, MetaData};
+
+/// 已加载书源的概要信息
+#[derive(Debug, Clone)]
+pub struct SourceInfo {
+    pub metadata: MetaData,
+    pub enabled: bool,
+}
+
+/// 书源的初始化方式，批量检查更新时用它为同一书源创建更多工作线程
+#[derive(Clone)]
+pub(crate) struct SourceInit(Arc<dyn Fn() -> Result<BookCore, BookCoreError> + Send + Sync>);
+
+impl SourceInit {
+    fn new<F>(init: F) -> Self
+    where
+        F: Fn() -> Result<BookCore, BookCoreError> + Send + Sync + 'static,
+    {
+        SourceInit(Arc::new(init))
+    }
+
+    pub(crate) fn init(&self) -> Result<BookCore, BookCoreError> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for SourceInit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SourceInit")
+    }
+}
+
+#[derive(Debug)]
+struct RegisteredSource {
+    handle: BookCoreHandle,
+    init: SourceInit,
+    metadata: MetaData,
+    enabled: bool,
+    /// 通过 `load`/`upgrade` 加载时脚本内容的指纹，用于在执行脚本前识别重复加载
+    fingerprint: Option<u64>,
+}
+
+/// 以 metadata.uuid 为键管理多个书源
+///
+/// 每个书源运行在各自的 BookCoreHandle 上，卸载或替换后旧的工作线程会在句柄全部释放后退出
+#[derive(Debug, Default)]
+pub struct SourceRegistry {
+    sources: HashMap<String, RegisteredSource>,
+}
+
+impl SourceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加载书源
+    ///
+    /// 与已加载的脚本内容完全相同时直接返回 `DuplicateSource`，不会执行脚本；
+    /// 其他脚本声明了已被占用的 uuid 时返回 `SourceConflict`，更新版本应使用 `upgrade`
+    pub fn load(&mut self, code: String) -> Result<MetaData, BookCoreError> {
+        let fingerprint = fingerprint(&code);
+        if let Some(key) = self.find_fingerprint(fingerprint) {
+            return Err(BookCoreError::DuplicateSource(key));
+        }
+        let init = SourceInit::new(move || BookCore::init(code.clone()));
+        let (key, handle, metadata) = Self::spawn(&init)?;
+        if self.sources.contains_key(&key) {
+            return Err(BookCoreError::SourceConflict(key));
+        }
+        self.insert(key, handle, init, metadata.clone(), true, Some(fingerprint));
+        Ok(metadata)
+    }
+
+    /// 以自定义的方式创建并加载书源，uuid 已被占用时返回 `DuplicateSource`
+    ///
+    /// uuid 只有执行脚本后才能读到，因此无论是否重复都会先启动工作线程并执行一次脚本。
+    /// `init` 会被保留下来，批量检查更新时用它为该书源创建更多工作线程
+    pub fn load_with<F>(&mut self, init: F) -> Result<MetaData, BookCoreError>
+    where
+        F: Fn() -> Result<BookCore, BookCoreError> + Send + Sync + 'static,
+    {
+        let init = SourceInit::new(init);
+        let (key, handle, metadata) = Self::spawn(&init)?;
+        if self.sources.contains_key(&key) {
+            return Err(BookCoreError::DuplicateSource(key));
+        }
+        self.insert(key, handle, init, metadata.clone(), true, None);
+        Ok(metadata)
+    }
+
+    /// 加载或升级书源：uuid 未加载时直接加载，已加载时只接受更高的 version
+    ///
+    /// 书源以 uuid 为标识，升级时可以修改名称。升级后保留原有的启用状态；
+    /// 与已加载的脚本内容完全相同时直接返回 `OutdatedVersion`，不会执行脚本
+    pub fn upgrade(&mut self, code: String) -> Result<MetaData, BookCoreError> {
+        let fingerprint = fingerprint(&code);
+        if let Some(key) = self.find_fingerprint(fingerprint) {
+            let version = self.sources[&key].metadata.version.clone();
+            return Err(BookCoreError::OutdatedVersion {
+                uuid: key,
+                loaded: version.clone(),
+                offered: version,
+            });
+        }
+        let init = SourceInit::new(move || BookCore::init(code.clone()));
+        self.replace(init, Some(fingerprint))
+    }
+
+    /// 以自定义的方式加载或升级书源，版本规则与 `upgrade` 相同
+    ///
+    /// 与 `load_with` 一样会先执行一次脚本才能读到 uuid 与 version，`init` 同样会被保留下来
+    pub fn upgrade_with<F>(&mut self, init: F) -> Result<MetaData, BookCoreError>
+    where
+        F: Fn() -> Result<BookCore, BookCoreError> + Send + Sync + 'static,
+    {
+        self.replace(SourceInit::new(init), None)
+    }
+
+    fn replace(
+        &mut self,
+        init: SourceInit,
+        fingerprint: Option<u64>,
+    ) -> Result<MetaData, BookCoreError> {
+        let (key, handle, metadata) = Self::spawn(&init)?;
+        let enabled = match self.sources.get(&key) {
+            Some(loaded) => {
+                if compare_versions(&metadata.version, &loaded.metadata.version)
+                    != Ordering::Greater
+                {
+                    return Err(BookCoreError::OutdatedVersion {
+                        uuid: key,
+                        loaded: loaded.metadata.version.clone(),
+                        offered: metadata.version,
+                    });
+                }
+                loaded.enabled
+            }
+            None => true,
+        };
+        self.insert(key, handle, init, metadata.clone(), enabled, fingerprint);
+        Ok(metadata)
+    }
+
+    pub fn unload(&mut self, uuid: &str) -> Result<MetaData, BookCoreError> {
+        let key = normalize_uuid(uuid)?;
+        self.sources
+            .remove(&key)
+            .map(|source| source.metadata)
+            .ok_or(BookCoreError::SourceNotFound(key))
+    }
+
+    pub fn enable(&mut self, uuid: &str) -> Result<(), BookCoreError> {
+        self.set_enabled(uuid, true)
+    }
+
+    pub fn disable(&mut self, uuid: &str) -> Result<(), BookCoreError> {
+        self.set_enabled(uuid, false)
+    }
+
+    pub fn contains(&self, uuid: &str) -> bool {
+        normalize_uuid(uuid)
+            .map(|key| self.sources.contains_key(&key))
+            .unwrap_or(false)
+    }
+
+    /// 获取书源的句柄，已禁用的书源返回错误
+    pub fn get(&self, uuid: &str) -> Result<BookCoreHandle, BookCoreError> {
+        self.worker(uuid).map(|(_, handle, _)| handle)
+    }
+
+    /// 获取书源的键、句柄与初始化方式，已禁用的书源返回错误
+    pub(crate) fn worker(
+        &self,
+        uuid: &str,
+    ) -> Result<(String, BookCoreHandle, SourceInit), BookCoreError> {
+        let key = normalize_uuid(uuid)?;
+        match self.sources.get(&key) {
+            Some(source) if source.enabled => {
+                let (handle, init) = (source.handle.clone(), source.init.clone());
+                Ok((key, handle, init))
+            }
+            Some(_) => Err(BookCoreError::SourceDisabled(key)),
+            None => Err(BookCoreError::SourceNotFound(key)),
+        }
+    }
+
+    /// 列出所有已加载书源，按名称排序
+    pub fn list(&self) -> Vec<SourceInfo> {
+        let mut sources: Vec<SourceInfo> = self
+            .sources
+            .values()
+            .map(|source| SourceInfo {
+                metadata: source.metadata.clone(),
+                enabled: source.enabled,
+            })
+            .collect();
+        sources.sort_by(|a, b| a.metadata.name.cmp(&b.metadata.name));
+        sources
+    }
+
+    fn spawn(init: &SourceInit) -> Result<(String, BookCoreHandle, MetaData), BookCoreError> {
+        let init = init.clone();
+        let handle = BookCoreHandle::spawn_with(move || init.init())?;
+        let metadata = handle.get_metadata()?;
+        let key = normalize_uuid(&metadata.uuid)?;
+        Ok((key, handle, metadata))
+    }
+
+    fn insert(
+        &mut self,
+        key: String,
+        handle: BookCoreHandle,
+        init: SourceInit,
+        metadata: MetaData,
+        enabled: bool,
+        fingerprint: Option<u64>,
+    ) {
+        self.sources.insert(
+            key,
+            RegisteredSource {
+                handle,
+                init,
+                metadata,
+                enabled,
+                fingerprint,
+            },
+        );
+    }
+
+    fn find_fingerprint(&self, fingerprint: u64) -> Option<String> {
+        self.sources
+            .iter()
+            .find(|(_, source)| source.fingerprint == Some(fingerprint))
+            .map(|(key, _)| key.clone())
+    }
+
+    fn set_enabled(&mut self, uuid: &str, enabled: bool) -> Result<(), BookCoreError> {
+        let key = normalize_uuid(uuid)?;
+        match self.sources.get_mut(&key) {
+            Some(source) => {
+                source.enabled = enabled;
+                Ok(())
+            }
+            None => Err(BookCoreError::SourceNotFound(key)),
+        }
+    }
+}
+
+/// 统一 uuid 的大小写和格式
+fn normalize_uuid(uuid: &str) -> Result<String, BookCoreError> {
+    Uuid::parse_str(uuid.trim())
+        .map(|uuid| uuid.to_string())
+        .map_err(|_| BookCoreError::InvalidUuid(uuid.to_string()))
+}
+
+fn fingerprint(code: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    code.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 按点分段比较版本号，数字段按数值比较，缺失的段视为 0
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let a: Vec<&str> = a.trim().trim_start_matches('v').split('.').collect();
+    let b: Vec<&str> = b.trim().trim_start_matches('v').split('.').collect();
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or("0");
+        let y = b.get(i).copied().unwrap_or("0");
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
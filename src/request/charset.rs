@@ -2,19 +2,40 @@ use boa_engine::{js_string, object::ObjectInitializer, Context, JsObject, JsResu
 use chardet::detect;
 use encoding_rs::{Encoding, UTF_8};
 use regex::Regex;
-use reqwest::Response;
+use reqwest::{header::HeaderMap, Response, StatusCode};
 
-pub async fn decode_response(response: Response, ctx: &mut Context) -> JsResult<JsObject> {
+/// 已读取完毕的响应，网络部分在 tokio 运行时中完成，解码交给脚本线程
+#[derive(Debug)]
+pub struct RawResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub bytes: Vec<u8>,
+}
+
+impl RawResponse {
+    pub async fn read(response: Response) -> reqwest::Result<Self> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.bytes().await?.to_vec();
+        Ok(RawResponse {
+            status,
+            headers,
+            bytes,
+        })
+    }
+}
+
+pub fn decode_response(response: RawResponse, ctx: &mut Context) -> JsResult<JsObject> {
     let obj = ObjectInitializer::new(ctx).build();
-    obj.set(js_string!("ok"), response.status().is_success(), true, ctx)?;
-    obj.set(js_string!("status"), response.status().as_u16(), true, ctx)?;
+    obj.set(js_string!("ok"), response.status.is_success(), true, ctx)?;
+    obj.set(js_string!("status"), response.status.as_u16(), true, ctx)?;
     obj.set(
         js_string!("statusText"),
-        js_string!(response.status().canonical_reason().unwrap_or("")),
+        js_string!(response.status.canonical_reason().unwrap_or("")),
         true,
         ctx,
     )?;
-    let headers = response.headers().clone();
+    let headers = response.headers;
     let headers_obj = ObjectInitializer::new(ctx).build();
     for (name, value) in headers.iter() {
         if let Ok(value_str) = value.to_str() {
@@ -35,7 +56,7 @@ pub async fn decode_response(response: Response, ctx: &mut Context) -> JsResult<
                 })
         });
     // 2. 如果没有在 header 中找到编码，尝试从 meta 标签获取
-    let bytes = response.bytes().await.unwrap_or_default();
+    let bytes = response.bytes;
     if encoding.is_none() {
         encoding = extract_charset_from_meta(&bytes);
     }
@@ -1,26 +1,36 @@
-use std::time::Duration;
-
 use boa_engine::{
-    class::Class, js_string, Context, JsData, JsNativeError, JsResult, JsValue, NativeFunction,
+    class::Class, js_string, Context, JsArgs, JsData, JsError, JsNativeError, JsResult, JsValue,
+    NativeFunction,
 };
 use boa_gc::{Finalize, Trace};
-use reqwest::{Client, Method};
+use reqwest::Method;
 
-use super::{charset::decode_response, options::Options};
+use super::{
+    charset::{decode_response, RawResponse},
+    options::Options,
+    scope::RequestScope,
+};
 
 #[derive(Debug, Trace, Finalize, JsData)]
 struct JReqwest {}
 
 impl JReqwest {
     fn request(method: Method, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
-        let mut url = args.get(0).unwrap().to_string(ctx)?.to_std_string_escaped();
-        let options = args.get(1).unwrap();
-        let options = Options::from_js_value(options, ctx).unwrap_or_default();
+        let scope = RequestScope::current(ctx);
+        scope.check()?;
+        let mut url = args
+            .get_or_undefined(0)
+            .to_string(ctx)?
+            .to_std_string_escaped();
+        let options = Options::from_js_value(args.get_or_undefined(1), ctx).unwrap_or_default();
         let gbk = options.gbk;
         if let Some(query) = options.query.clone() {
             if gbk {
                 let mut query_vec = Vec::new();
-                for (key, value) in query.as_object().unwrap().iter() {
+                let query_obj = query
+                    .as_object()
+                    .ok_or_else(|| JsNativeError::typ().with_message("query 应为对象"))?;
+                for (key, value) in query_obj.iter() {
                     query_vec.push(format!("{}={}", key, value));
                 }
                 let query_str = query_vec.join("&");
@@ -33,12 +43,10 @@ impl JReqwest {
                 }
             }
         }
-        let client = Client::builder()
-            .danger_accept_invalid_certs(true)
-            .use_rustls_tls()
-            .timeout(Duration::from_secs(options.timeout.as_secs()))
-            .build()
-            .expect("Failed to create reqwest client");
+        let client = scope
+            .defaults
+            .client(options.timeout)
+            .map_err(|e| network_error(format!("Failed to create client: {}", e), ctx))?;
         let mut request = client.request(method, url);
         if !options.headers.is_empty() {
             request = request.headers(options.headers);
@@ -93,20 +101,12 @@ impl JReqwest {
             }
         }
 
-        let response = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                request.send().await.map_err(|e| {
-                    JsNativeError::typ().with_message(format!("Request failed: {}", e))
-                })
-            })
-        })?;
+        let response = scope
+            .block_on(async move { RawResponse::read(request.send().await?).await })?
+            .map_err(|e| network_error(format!("Request failed: {}", e), ctx))?;
 
-        let response = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                decode_response(response, ctx).await.map_err(|e| {
-                    JsNativeError::typ().with_message(format!("Failed to decode response: {}", e))
-                })
-            })
+        let response = decode_response(response, ctx).map_err(|e| {
+            JsNativeError::typ().with_message(format!("Failed to decode response: {}", e))
         })?;
 
         Ok(response.into())
@@ -155,6 +155,13 @@ impl Class for JReqwest {
     }
 }
 
+/// 网络错误以 NetworkError 命名抛出，方便在 rust 侧区分
+fn network_error(message: String, ctx: &mut Context) -> JsError {
+    let error = JsNativeError::error().with_message(message).to_opaque(ctx);
+    let _ = error.set(js_string!("name"), js_string!("NetworkError"), false, ctx);
+    JsError::from_opaque(error.into())
+}
+
 pub fn define_request(context: &mut Context) {
     context
         .register_global_class::<JReqwest>()
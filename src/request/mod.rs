@@ -1,3 +1,4 @@
 pub mod charset;
 pub mod jreqwest;
 pub mod options;
+pub mod scope;
@@ -7,7 +7,7 @@ use serde_json::Value;
 #[derive(Debug)]
 pub struct Options {
     pub headers: HeaderMap,
-    pub timeout: Duration,
+    pub timeout: Option<Duration>,
     pub body: Option<String>,
     pub query: Option<Value>,
     pub form: Option<Value>,
@@ -20,7 +20,7 @@ impl Default for Options {
     fn default() -> Self {
         Options {
             headers: HeaderMap::new(),
-            timeout: Duration::from_secs(5),
+            timeout: None,
             body: None,
             query: None,
             form: None,
@@ -32,19 +32,22 @@ impl Default for Options {
 
 impl Options {
     pub fn from_js_value(value: &JsValue, ctx: &mut Context) -> JsResult<Self> {
-        let obj = value.as_object().unwrap();
+        let Some(obj) = value.as_object() else {
+            return Ok(Options::default());
+        };
         // 生成 headers
         let mut headers = HeaderMap::new();
         let headers_value = obj.get(js_string!("headers"), ctx)?;
-        if !headers_value.is_null_or_undefined() {
-            let js_headers = headers_value.as_object().unwrap();
+        if let Some(js_headers) = headers_value.as_object() {
             for key in js_headers.own_property_keys(ctx)? {
                 let value = js_headers
                     .get(key.clone(), ctx)?
                     .to_string(ctx)?
                     .to_std_string_escaped();
-                if let Ok(key) = HeaderName::from_str(&key.to_string()) {
-                    headers.insert(key, value.parse().unwrap());
+                if let (Ok(key), Ok(value)) =
+                    (HeaderName::from_str(&key.to_string()), value.parse())
+                {
+                    headers.insert(key, value);
                 }
             }
         }
@@ -55,10 +58,10 @@ impl Options {
             gbk = gbk_value.as_boolean().unwrap();
         }
         // 生成超时
-        let mut timeout = Duration::from_secs(5);
+        let mut timeout = None;
         let timeout_value = obj.get(js_string!("timeout"), ctx)?;
-        if timeout_value.is_number() {
-            timeout = Duration::from_secs(timeout_value.as_number().unwrap() as u64);
+        if let Some(seconds) = timeout_value.as_number() {
+            timeout = Some(Duration::from_secs(seconds as u64));
         }
         // 生成请求body
         let mut body = None;
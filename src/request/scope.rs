@@ -0,0 +1,173 @@
+use std::{
+    future::Future,
+    sync::mpsc::{self, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use boa_engine::{Context, JsData, JsNativeError, JsResult};
+use boa_gc::{Finalize, Trace};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+use crate::{cancel::CancellationToken, Proxy, ProxyType};
+
+/// 未指定运行时的 BookCore 共用的 tokio 运行时
+static SHARED_RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+    Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("book-core")
+        .build()
+        .expect("Failed to build shared tokio runtime")
+});
+
+/// 等待请求期间检查截止时间与取消状态的间隔
+const CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 返回共享运行时的句柄
+pub fn shared_handle() -> Handle {
+    SHARED_RUNTIME.handle().clone()
+}
+
+/// JReqwest 的默认配置，脚本未在 options 中指定时使用
+#[derive(Debug, Clone)]
+pub struct RequestDefaults {
+    pub timeout: Duration,
+    pub accept_invalid_certs: bool,
+    pub user_agent: Option<String>,
+    pub proxy: Option<Proxy>,
+}
+
+impl Default for RequestDefaults {
+    fn default() -> Self {
+        RequestDefaults {
+            timeout: Duration::from_secs(5),
+            accept_invalid_certs: true,
+            user_agent: None,
+            proxy: None,
+        }
+    }
+}
+
+impl RequestDefaults {
+    /// 按默认配置创建 client，`timeout` 为脚本指定的超时
+    pub fn client(&self, timeout: Option<Duration>) -> reqwest::Result<Client> {
+        let mut builder = Client::builder()
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
+            .use_rustls_tls()
+            .timeout(timeout.unwrap_or(self.timeout));
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(to_reqwest_proxy(proxy)?);
+        }
+        builder.build()
+    }
+}
+
+fn to_reqwest_proxy(proxy: &Proxy) -> reqwest::Result<reqwest::Proxy> {
+    let scheme = match proxy.proxy_type {
+        None | Some(ProxyType::Http) => "http",
+        Some(ProxyType::Https) => "https",
+        Some(ProxyType::Socks4) => "socks4",
+        Some(ProxyType::Socks5) => "socks5",
+    };
+    let mut result = reqwest::Proxy::all(format!("{}://{}:{}", scheme, proxy.host, proxy.port))?;
+    if let Some(username) = &proxy.username {
+        result = result.basic_auth(username, proxy.password.as_deref().unwrap_or_default());
+    }
+    Ok(result)
+}
+
+/// 挂在 realm 上的请求上下文，JReqwest 通过它获取网络请求所需的运行时、本次调用的截止时间与取消令牌
+#[derive(Debug, Clone, Trace, Finalize, JsData)]
+pub struct RequestScope {
+    #[unsafe_ignore_trace]
+    pub handle: Handle,
+    #[unsafe_ignore_trace]
+    pub deadline: Option<Instant>,
+    #[unsafe_ignore_trace]
+    pub cancel: Option<CancellationToken>,
+    #[unsafe_ignore_trace]
+    pub defaults: RequestDefaults,
+}
+
+impl Default for RequestScope {
+    fn default() -> Self {
+        RequestScope {
+            handle: shared_handle(),
+            deadline: None,
+            cancel: None,
+            defaults: RequestDefaults::default(),
+        }
+    }
+}
+
+impl RequestScope {
+    pub fn current(ctx: &Context) -> Self {
+        ctx.realm()
+            .host_defined()
+            .get::<RequestScope>()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn install(self, ctx: &Context) {
+        ctx.realm().host_defined_mut().insert(self);
+    }
+
+    /// 检查点：已取消或超过截止时间时抛出脚本无法捕获的错误
+    pub fn check(&self) -> JsResult<()> {
+        if self
+            .cancel
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(JsNativeError::runtime_limit()
+                .with_message("execution was cancelled")
+                .into());
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(JsNativeError::runtime_limit()
+                .with_message("execution deadline exceeded")
+                .into());
+        }
+        Ok(())
+    }
+
+    /// 在运行时上执行 future，并阻塞当前（脚本）线程等待结果
+    ///
+    /// 不依赖 `Handle::current()`，因此在 current-thread 运行时或异步任务中调用也不会 panic；
+    /// 但运行时本身必须由其他线程驱动，否则会一直等待。取消或超过截止时间时会丢弃未完成的 future
+    pub fn block_on<F>(&self, future: F) -> JsResult<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.check()?;
+        let (tx, rx) = mpsc::channel();
+        let task = self.handle.spawn(async move {
+            let _ = tx.send(future.await);
+        });
+        loop {
+            match rx.recv_timeout(CHECK_INTERVAL) {
+                Ok(output) => return Ok(output),
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Err(err) = self.check() {
+                        task.abort();
+                        return Err(err);
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(JsNativeError::error()
+                        .with_message("Request task was dropped")
+                        .into())
+                }
+            }
+        }
+    }
+}
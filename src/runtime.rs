@@ -1,4 +1,4 @@
-use boa_engine::{js_string, property::Attribute};
+use boa_engine::{js_string, property::Attribute, Context};
 use boa_runtime::Console;
 
 use crate::{
@@ -12,29 +12,76 @@ use crate::{
     prototype::{object::extend_object, string::extend_string},
     request::jreqwest::define_request,
     scraper::jscraper::define_scraper,
-    BookCore,
 };
 
-pub fn init_runtime(core: &mut BookCore) {
-    let context = &mut core.context;
-    let console = Console::init(context);
-    context
-        .register_global_property(
-            js_string!("console"),
-            console,
-            Attribute::WRITABLE | Attribute::CONFIGURABLE,
-        )
-        .expect("Failed to register console");
+/// 注册到脚本中的内置全局对象，默认全部启用
+///
+/// 环境变量相关的 setEnv/getEnv 等始终注册，BookCore 依赖它们保存配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Globals {
+    /// console
+    pub console: bool,
+    /// JReqwest
+    pub request: bool,
+    /// JScraper
+    pub scraper: bool,
+    /// Aes、Hmac
+    pub crypto: bool,
+    /// xml2Json
+    pub xml: bool,
+    /// randString、uuid、isUuid
+    pub utils: bool,
+    /// String 与 Object 原型上的扩展方法
+    pub prototypes: bool,
+}
+
+impl Default for Globals {
+    fn default() -> Self {
+        Globals {
+            console: true,
+            request: true,
+            scraper: true,
+            crypto: true,
+            xml: true,
+            utils: true,
+            prototypes: true,
+        }
+    }
+}
+
+pub fn init_runtime(context: &mut Context, globals: &Globals) {
+    if globals.console {
+        let console = Console::init(context);
+        context
+            .register_global_property(
+                js_string!("console"),
+                console,
+                Attribute::WRITABLE | Attribute::CONFIGURABLE,
+            )
+            .expect("Failed to register console");
+    }
     // define_envs(context);
     regist_envs(context);
-    define_request(context);
-    define_scraper(context);
-    define_aes_crypto(context);
-    define_hmac(context);
-    regist_xml_to_json(context);
-    regist_rand_str(context);
-    regist_uuid(context);
-    regist_is_uuid(context);
-    extend_string(context);
-    extend_object(context);
+    if globals.request {
+        define_request(context);
+    }
+    if globals.scraper {
+        define_scraper(context);
+    }
+    if globals.crypto {
+        define_aes_crypto(context);
+        define_hmac(context);
+    }
+    if globals.xml {
+        regist_xml_to_json(context);
+    }
+    if globals.utils {
+        regist_rand_str(context);
+        regist_uuid(context);
+        regist_is_uuid(context);
+    }
+    if globals.prototypes {
+        extend_string(context);
+        extend_object(context);
+    }
 }
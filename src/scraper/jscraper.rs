@@ -7,6 +7,8 @@ use boa_engine::{
 use boa_gc::{Finalize, Trace};
 use scraper::Html;
 
+use crate::request::scope::RequestScope;
+
 #[derive(Debug, Trace, Finalize, JsData)]
 struct JScraper {
     html: String,
@@ -14,7 +16,8 @@ struct JScraper {
 }
 
 impl JScraper {
-    fn text(this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    fn text(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        RequestScope::current(context).check()?;
         if let Some(object) = this.as_object() {
             if let Some(scraper) = object.downcast_ref::<JScraper>() {
                 let document = Html::parse_document(&scraper.html);
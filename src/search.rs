@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::deserialize, BookCore, BookCoreError, SearchBook};
+
+/// 传给书源 `search` 的参数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchQuery {
+    pub key: String,
+    pub page: u32,
+    pub count: u32,
+    /// 上一页返回的 `next_cursor`，按游标分页的站点使用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Value>,
+    /// 以筛选项 id 为键的取值
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub filters: BTreeMap<String, FilterValue>,
+}
+
+impl SearchQuery {
+    pub fn new(key: impl Into<String>) -> Self {
+        SearchQuery {
+            key: key.into(),
+            page: 1,
+            count: 20,
+            cursor: None,
+            filters: BTreeMap::new(),
+        }
+    }
+
+    pub fn page(mut self, page: u32) -> Self {
+        self.page = page;
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn cursor(mut self, cursor: Value) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    pub fn filter(mut self, id: impl Into<String>, value: FilterValue) -> Self {
+        self.filters.insert(id.into(), value);
+        self
+    }
+
+    /// 下一页的查询，`page` 加一并带上本页返回的游标
+    pub fn next(&self, page: &SearchPage) -> Self {
+        SearchQuery {
+            page: self.page + 1,
+            cursor: page.next_cursor.clone(),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SearchFilterType {
+    /// 单选
+    #[serde(rename = "select")]
+    Select,
+    /// 多选
+    #[serde(rename = "multiSelect")]
+    MultiSelect,
+    /// 数值区间，例如字数
+    #[serde(rename = "range")]
+    Range,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FilterOption {
+    pub label: String,
+    pub value: String,
+}
+
+/// 书源通过 `searchFilters` 声明的搜索筛选项
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchFilter {
+    pub id: String,
+    pub label: String,
+    #[serde(rename = "filterType")]
+    pub filter_type: SearchFilterType,
+    /// select 与 multiSelect 的可选项
+    pub options: Option<Vec<FilterOption>>,
+    pub default: Option<FilterValue>,
+    /// range 的取值范围
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub unit: Option<String>,
+}
+
+/// 筛选项的取值，select 为单个值，multiSelect 为数组，range 为 `{ min, max }`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum FilterValue {
+    Single(String),
+    Multiple(Vec<String>),
+    Range { min: Option<f64>, max: Option<f64> },
+}
+
+impl SearchFilter {
+    /// 检查取值是否符合筛选项的声明
+    pub fn validate(&self, value: &FilterValue) -> Result<(), BookCoreError> {
+        let invalid = |message: String| BookCoreError::InvalidFilter {
+            id: self.id.clone(),
+            message,
+        };
+        let known = |value: &String| {
+            self.options
+                .as_ref()
+                .is_none_or(|options| options.iter().any(|option| &option.value == value))
+        };
+        match (self.filter_type, value) {
+            (SearchFilterType::Select, FilterValue::Single(value)) => {
+                if !known(value) {
+                    return Err(invalid(format!("unknown option `{}`", value)));
+                }
+            }
+            (SearchFilterType::MultiSelect, FilterValue::Multiple(values)) => {
+                if let Some(value) = values.iter().find(|value| !known(value)) {
+                    return Err(invalid(format!("unknown option `{}`", value)));
+                }
+            }
+            (SearchFilterType::Range, FilterValue::Range { min, max }) => {
+                let out_of_bounds = |value: f64| {
+                    self.min.is_some_and(|min| value < min)
+                        || self.max.is_some_and(|max| value > max)
+                };
+                if min.is_some_and(out_of_bounds) || max.is_some_and(out_of_bounds) {
+                    return Err(invalid("value is out of range".to_string()));
+                }
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(invalid("min is greater than max".to_string()));
+                    }
+                }
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "value does not match {:?}",
+                    self.filter_type
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 按声明校验一组筛选项取值，未声明的筛选项视为错误
+pub(crate) fn validate_filters(
+    filters: &[SearchFilter],
+    values: &BTreeMap<String, FilterValue>,
+) -> Result<(), BookCoreError> {
+    for (id, value) in values {
+        let Some(filter) = filters.iter().find(|filter| &filter.id == id) else {
+            return Err(BookCoreError::InvalidFilter {
+                id: id.clone(),
+                message: "filter is not declared by the source".to_string(),
+            });
+        };
+        filter.validate(value)?;
+    }
+    Ok(())
+}
+
+/// 一页搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchPage {
+    pub items: Vec<SearchBook>,
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+    pub total: Option<u64>,
+    /// 不透明的游标，原样放入下一次查询的 `cursor`
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSearchPage {
+    items: Vec<SearchBook>,
+    #[serde(rename = "hasMore")]
+    has_more: Option<bool>,
+    total: Option<u64>,
+    #[serde(rename = "nextCursor")]
+    next_cursor: Option<Value>,
+}
+
+impl SearchPage {
+    /// 兼容直接返回数组的书源：未声明 hasMore 时，有游标或返回满一页即认为还有下一页，
+    /// 不知道每页数量时只要本页不为空就认为还有下一页
+    pub(crate) fn from_value(value: Value, count: Option<u32>) -> Result<Self, BookCoreError> {
+        let raw = if value.is_array() {
+            RawSearchPage {
+                items: deserialize(value)?,
+                has_more: None,
+                total: None,
+                next_cursor: None,
+            }
+        } else {
+            deserialize(value)?
+        };
+        let has_more = raw.has_more.unwrap_or_else(|| {
+            raw.next_cursor.is_some()
+                || (!raw.items.is_empty()
+                    && count.is_none_or(|count| raw.items.len() >= count as usize))
+        });
+        Ok(SearchPage {
+            items: raw.items,
+            has_more,
+            total: raw.total,
+            next_cursor: raw.next_cursor,
+        })
+    }
+}
+
+impl BookCore {
+    /// 分页搜索，书源的 `search` 可以返回数组，也可以返回 `{ items, hasMore, total, nextCursor }`
+    ///
+    /// 带有筛选项时会先按 `searchFilters` 的声明校验取值
+    pub fn search(&mut self, query: SearchQuery) -> Result<SearchPage, BookCoreError> {
+        if !query.filters.is_empty() {
+            let filters = self.get_search_filters()?;
+            validate_filters(&filters, &query.filters)?;
+        }
+        let count = query.count;
+        let args = serde_json::to_value(&query).expect("SearchQuery is always serializable");
+        let value = self.invoke("search", args)?;
+        SearchPage::from_value(value, Some(count))
+    }
+
+    /// 书源声明的搜索筛选项，未声明时为空
+    pub fn get_search_filters(&mut self) -> Result<Vec<SearchFilter>, BookCoreError> {
+        Ok(self.read_optional("searchFilters")?.unwrap_or_default())
+    }
+}
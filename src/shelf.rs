@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    catalog::Paging, error::deserialize, ActionResult, BookCore, BookCoreError, BookLatestChapter,
+    LoginRequired,
+};
+
+/// 站点账号书架上的一本书
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ShelfBook {
+    pub id: String,
+    pub name: String,
+    pub author: Option<String>,
+    pub cover: Option<String>,
+    #[serde(rename = "latestChapter")]
+    pub latest_chapter: Option<BookLatestChapter>,
+    /// 站点记录的阅读进度
+    #[serde(rename = "readChapterId")]
+    pub read_chapter_id: Option<String>,
+    #[serde(rename = "updateTime")]
+    pub update_time: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShelfResult {
+    Books(Vec<ShelfBook>),
+    LoginRequired(LoginRequired),
+}
+
+#[derive(Debug, Deserialize)]
+struct RawShelfPage {
+    items: Vec<ShelfBook>,
+    continuation: Option<Value>,
+}
+
+impl BookCore {
+    /// 书源是否提供账号书架
+    pub fn has_shelf(&mut self) -> bool {
+        self.has_entry("shelf")
+    }
+
+    /// 获取账号书架上的全部书籍
+    ///
+    /// 书源的 `shelf` 以 `{ page, continuation }` 调用，可以返回数组，也可以返回
+    /// `{ items, continuation }` 分页；未登录时返回 `{ type: 'loginRequired', form }`，
+    /// 宿主据此展示对应的表单，提交后 envs 中的凭据会在下次调用时生效。
+    /// 所有分页在同一次执行预算内完成，超过 `max_list_pages` 页时返回 `BookCoreError::TooManyPages`，
+    /// 返回已经出现过的 continuation 时返回 `BookCoreError::RepeatedContinuation`，不会只返回一部分书籍
+    pub fn get_shelf(&mut self) -> Result<ShelfResult, BookCoreError> {
+        self.run(|core| core.fetch_shelf())
+    }
+
+    fn fetch_shelf(&mut self) -> Result<ShelfResult, BookCoreError> {
+        let mut books = Vec::new();
+        let mut paging = Paging::new(self.max_list_pages);
+        let mut continuation = None;
+        for page in 1.. {
+            self.start_page(&paging, page)?;
+            let value: Value = self.invoke(
+                "shelf",
+                json!({ "page": page, "continuation": continuation }),
+            )?;
+            if value.is_array() {
+                books.extend(deserialize::<Vec<ShelfBook>>(value)?);
+                break;
+            }
+            if value.get("type").and_then(Value::as_str) == Some("loginRequired") {
+                return Ok(ShelfResult::LoginRequired(deserialize(value)?));
+            }
+            let raw: RawShelfPage = deserialize(value)?;
+            books.extend(raw.items);
+            let Some(next) = raw.continuation else {
+                break;
+            };
+            if !paging.follow(&next) {
+                return Err(BookCoreError::RepeatedContinuation(page));
+            }
+            continuation = Some(next);
+        }
+        Ok(ShelfResult::Books(books))
+    }
+
+    /// 把书加入账号书架，书源的 `addToShelf` 以 `{ bid }` 调用，返回值同动作
+    pub fn add_to_shelf(&mut self, bid: String) -> Result<ActionResult, BookCoreError> {
+        let value = self.invoke("addToShelf", json!({ "bid": bid }))?;
+        self.action_result(value)
+    }
+
+    /// 把书移出账号书架，书源的 `removeFromShelf` 以 `{ bid }` 调用，返回值同动作
+    pub fn remove_from_shelf(&mut self, bid: String) -> Result<ActionResult, BookCoreError> {
+        let value = self.invoke("removeFromShelf", json!({ "bid": bid }))?;
+        self.action_result(value)
+    }
+}
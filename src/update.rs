@@ -0,0 +1,178 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use futures_util::{future::join_all, stream::FuturesUnordered, StreamExt};
+use serde_json::json;
+use tokio::sync::Semaphore;
+
+use crate::{
+    registry::SourceInit, BookCore, BookCoreError, BookCoreHandle, BookLatestChapter,
+    SourceRegistry,
+};
+
+/// 同一书源默认同时执行的检查数
+pub const DEFAULT_UPDATE_IN_FLIGHT: usize = 2;
+
+/// 需要检查更新的一本书
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateTarget {
+    /// 书源的 metadata.uuid
+    pub uuid: String,
+    pub bid: String,
+    /// 本地记录的最新章节 id，为空时只要书源返回了最新章节就视为有更新
+    pub last_chapter_id: Option<String>,
+}
+
+impl UpdateTarget {
+    pub fn new(
+        uuid: impl Into<String>,
+        bid: impl Into<String>,
+        last_chapter_id: Option<String>,
+    ) -> Self {
+        UpdateTarget {
+            uuid: uuid.into(),
+            bid: bid.into(),
+            last_chapter_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateStatus {
+    Updated(BookLatestChapter),
+    /// 最新章节与本地记录一致，或书源没有返回最新章节
+    Unchanged,
+    Failed(BookCoreError),
+}
+
+/// 一本书的检查结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateCheck {
+    pub target: UpdateTarget,
+    pub status: UpdateStatus,
+}
+
+impl BookCore {
+    /// 获取书籍的最新章节
+    ///
+    /// 书源声明了 `latest` 时以 `{ bid }` 调用它，返回 `{ id, name, updateTime }` 或 null；
+    /// 否则取 `detail` 的 latestChapter
+    pub fn get_latest_chapter(
+        &mut self,
+        bid: String,
+    ) -> Result<Option<BookLatestChapter>, BookCoreError> {
+        if self.has_entry("latest") {
+            return self.invoke("latest", json!({ "bid": bid }));
+        }
+        Ok(self.get_book_detail(bid)?.latest_chapter)
+    }
+}
+
+impl SourceRegistry {
+    /// 批量检查书架更新，每得到一本书的结果调用一次 `on_result`，结果按完成顺序到达
+    ///
+    /// 不同书源的检查并行进行，互不等待；同一书源最多同时执行 `in_flight` 个检查。
+    /// 除书源自身的工作线程外，会按书源的初始化方式临时再创建最多 `in_flight - 1` 个工作线程，
+    /// 并复制当前的 env（登录状态等），检查结束后释放；检查期间对这些线程中 env 的修改不会保留。
+    /// 书源未加载或已禁用时直接返回失败
+    pub async fn check_updates(
+        &self,
+        targets: Vec<UpdateTarget>,
+        in_flight: usize,
+        mut on_result: impl FnMut(UpdateCheck),
+    ) {
+        let mut pending: Vec<(String, UpdateTarget)> = Vec::new();
+        let mut workers: HashMap<String, (BookCoreHandle, SourceInit, usize)> = HashMap::new();
+        for target in targets {
+            match self.worker(&target.uuid) {
+                Ok((key, handle, init)) => {
+                    workers.entry(key.clone()).or_insert((handle, init, 0)).2 += 1;
+                    pending.push((key, target));
+                }
+                Err(err) => on_result(UpdateCheck {
+                    target,
+                    status: UpdateStatus::Failed(err),
+                }),
+            }
+        }
+        let pools: HashMap<String, WorkerPool> = join_all(workers.into_iter().map(
+            |(key, (handle, init, count))| async move {
+                let pool = WorkerPool::spawn(handle, init, in_flight.max(1).min(count)).await;
+                (key, pool)
+            },
+        ))
+        .await
+        .into_iter()
+        .collect();
+        let mut checks: FuturesUnordered<_> = pending
+            .into_iter()
+            .map(|(key, target)| {
+                let pool = &pools[&key];
+                async move {
+                    let status = match pool.get_latest_chapter(target.bid.clone()).await {
+                        Ok(Some(latest))
+                            if target.last_chapter_id.as_deref() != Some(latest.id.as_str()) =>
+                        {
+                            UpdateStatus::Updated(latest)
+                        }
+                        Ok(_) => UpdateStatus::Unchanged,
+                        Err(err) => UpdateStatus::Failed(err),
+                    };
+                    UpdateCheck { target, status }
+                }
+            })
+            .collect();
+        while let Some(check) = checks.next().await {
+            on_result(check);
+        }
+    }
+}
+
+/// 同一书源的一组工作线程，每个线程同一时间只执行一个检查
+struct WorkerPool {
+    permits: Semaphore,
+    idle: Mutex<Vec<BookCoreHandle>>,
+}
+
+impl WorkerPool {
+    /// 以 `handle` 为首创建最多 `size` 个工作线程，额外的线程创建失败时少用几个，不影响检查
+    async fn spawn(handle: BookCoreHandle, init: SourceInit, size: usize) -> Self {
+        let mut handles = vec![handle.clone()];
+        if size > 1 {
+            if let Ok(envs) = handle.call_async(|core| core.get_envs()).await {
+                let extra = (1..size).map(|_| {
+                    let (init, envs) = (init.clone(), envs.clone());
+                    BookCoreHandle::spawn_with_async(move || {
+                        let mut core = init.init()?;
+                        core.set_envs(envs)?;
+                        Ok(core)
+                    })
+                });
+                handles.extend(join_all(extra).await.into_iter().flatten());
+            }
+        }
+        WorkerPool {
+            permits: Semaphore::new(handles.len()),
+            idle: Mutex::new(handles),
+        }
+    }
+
+    async fn get_latest_chapter(
+        &self,
+        bid: String,
+    ) -> Result<Option<BookLatestChapter>, BookCoreError> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+        let handle = self
+            .idle
+            .lock()
+            .unwrap()
+            .pop()
+            .expect("a permit always has an idle worker");
+        let result = handle.get_latest_chapter_async(bid).await;
+        self.idle.lock().unwrap().push(handle);
+        result
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use book_core::{ActionResult, BookCore, BookCoreError};
+use serde_json::{json, Map, Value};
+
+const JS: &str = r#"
+const actions = [
+    { label: '签到', action: 'checkIn' },
+    { label: '清除缓存', action: 'clearCache' },
+    {
+        label: '切换服务器',
+        action: 'switchServer',
+        form: {
+            title: '切换服务器',
+            fields: [{
+                fieldType: 'select', field: 'server', label: '服务器', required: true,
+                options: [{ label: '主线路', value: 'main' }, { label: '备用线路', value: 'backup' }],
+            }],
+        },
+    },
+    { label: '官网', action: 'openSite' },
+    { label: '书架', action: 'reload' },
+    { label: '失败', action: 'broken' },
+]
+const checkIn = () => '签到成功'
+const clearCache = () => undefined
+const switchServer = ({ server }) => ({ type: 'envs', envs: { server }, message: `已切换到 ${server}` })
+const openSite = () => ({ type: 'openUrl', url: 'https://example.com' })
+const reload = () => ({ type: 'refreshShelf' })
+const broken = () => ({ type: 'error', message: '服务器维护中' })
+"#;
+
+fn run(core: &mut BookCore, action: &str, values: Value) -> ActionResult {
+    core.perform_action(action, values.as_object().unwrap().clone())
+        .unwrap()
+}
+
+#[test]
+fn test_results() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    assert_eq!(
+        run(&mut core, "checkIn", json!({})),
+        ActionResult::Message {
+            message: "签到成功".to_string()
+        }
+    );
+    assert_eq!(run(&mut core, "clearCache", json!({})), ActionResult::Done);
+    assert_eq!(
+        run(&mut core, "openSite", json!({})),
+        ActionResult::OpenUrl {
+            url: "https://example.com".to_string()
+        }
+    );
+    assert_eq!(
+        run(&mut core, "reload", json!({})),
+        ActionResult::RefreshShelf { message: None }
+    );
+    assert_eq!(
+        run(&mut core, "broken", json!({})),
+        ActionResult::Error {
+            message: "服务器维护中".to_string()
+        }
+    );
+    let err = core.perform_action("missing", Map::new()).unwrap_err();
+    assert_eq!(err, BookCoreError::MissingEntry("missing".to_string()));
+}
+
+#[test]
+fn test_parameterised_action() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let actions = core.get_actions().unwrap();
+    assert!(actions[2].form.is_some());
+    let result = run(&mut core, "switchServer", json!({ "server": "nowhere" }));
+    let ActionResult::InvalidInput(errors) = result else {
+        panic!("expected invalid input, got {:?}", result);
+    };
+    assert!(errors.contains_key("server"));
+    let result = run(&mut core, "switchServer", json!({}));
+    assert_eq!(
+        result,
+        ActionResult::InvalidInput(BTreeMap::from([(
+            "server".to_string(),
+            "required".to_string()
+        )]))
+    );
+    let result = run(&mut core, "switchServer", json!({ "server": "backup" }));
+    assert!(matches!(
+        result,
+        ActionResult::EnvUpdate { message: Some(ref message), .. } if message == "已切换到 backup"
+    ));
+    assert_eq!(core.get_env("server".to_string()).unwrap(), json!("backup"));
+}
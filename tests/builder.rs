@@ -0,0 +1,109 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    thread,
+    time::Duration,
+};
+
+use book_core::{BookCore, BookCoreError, ExecutionLimits, Globals};
+use serde_json::{json, Value};
+
+const JS: &str = r#"
+const hasRequest = () => typeof JReqwest !== 'undefined'
+const hasScraper = () => typeof JScraper !== 'undefined'
+const readToken = () => getEnv('token')
+const search = ({ key }) => {
+    const res = JReqwest.get(key, {})
+    return [{ id: String(res.status), name: res.body }]
+}
+"#;
+
+/// 把收到的请求头原样作为响应体返回
+fn serve_echo() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 4096];
+        let mut request = Vec::new();
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = stream.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        let body = String::from_utf8_lossy(&request).to_lowercase();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn test_user_agent() {
+    let url = serve_echo();
+    let mut core = BookCore::builder()
+        .user_agent("librune-test")
+        .build(JS.to_string())
+        .unwrap();
+    let books = core.search_books(url, 1, 10).unwrap();
+    assert!(books[0].name.contains("user-agent: librune-test"));
+}
+
+#[test]
+fn test_globals() {
+    let mut core = BookCore::builder()
+        .globals(Globals {
+            request: false,
+            ..Globals::default()
+        })
+        .build(JS.to_string())
+        .unwrap();
+    assert!(!core.invoke::<bool>("hasRequest", Value::Null).unwrap());
+    assert!(core.invoke::<bool>("hasScraper", Value::Null).unwrap());
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    assert!(core.invoke::<bool>("hasRequest", Value::Null).unwrap());
+    let mut core = BookCore::builder()
+        .globals(Globals {
+            console: false,
+            ..Globals::default()
+        })
+        .build(JS.to_string())
+        .unwrap();
+    core.set_env("token".to_string(), json!("abc")).unwrap();
+    assert_eq!(core.get_env("token".to_string()).unwrap(), json!("abc"));
+    assert_eq!(
+        core.invoke::<String>("readToken", Value::Null).unwrap(),
+        "abc"
+    );
+}
+
+#[test]
+fn test_limits_cover_top_level() {
+    let err = BookCore::builder()
+        .limits(ExecutionLimits::new().loop_iterations(1_000))
+        .build("while (true) {}".to_string())
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::LimitExceeded(_)));
+}
+
+#[test]
+fn test_default_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        let (_stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(5));
+    });
+    let mut core = BookCore::builder()
+        .timeout(Duration::from_millis(200))
+        .build(JS.to_string())
+        .unwrap();
+    let err = core.search_books(url, 1, 10).unwrap_err();
+    assert!(matches!(err, BookCoreError::Network(_)));
+}
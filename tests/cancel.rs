@@ -0,0 +1,79 @@
+use std::{
+    io::Read,
+    net::TcpListener,
+    thread,
+    time::{Duration, Instant},
+};
+
+use book_core::{BookCore, BookCoreError, BookCoreHandle, CancellationToken};
+
+const JS: &str = r#"
+const search = ({ key }) => {
+    const res = JReqwest.get(key, {})
+    return [{ id: String(res.status), name: res.body }]
+}
+const parse = ({ count }) => {
+    let text = ''
+    for (let i = 0; i < count; i++) {
+        text = new JScraper('<p>' + i + '</p>').text()
+    }
+    return text
+}
+"#;
+
+/// 接受连接后一直不返回响应
+fn serve_never() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 4096];
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+        }
+    });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn test_cancel_pending_request() {
+    let url = serve_never();
+    let handle = BookCoreHandle::spawn(JS.to_string()).unwrap();
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        canceller.cancel();
+    });
+    let start = Instant::now();
+    let err = handle
+        .call_cancellable(&token, move |core| core.search_books(url, 1, 10))
+        .unwrap_err();
+    assert_eq!(err, BookCoreError::Cancelled);
+    assert!(start.elapsed() < Duration::from_secs(3));
+}
+
+#[test]
+fn test_cancel_at_check_point() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        canceller.cancel();
+    });
+    let start = Instant::now();
+    let err = core
+        .with_cancellation(&token, |core| {
+            core.invoke::<String>("parse", serde_json::json!({ "count": 100_000_000 }))
+        })
+        .unwrap_err();
+    assert_eq!(err, BookCoreError::Cancelled);
+    assert!(start.elapsed() < Duration::from_secs(10));
+    let text: String = core
+        .invoke("parse", serde_json::json!({ "count": 10 }))
+        .unwrap();
+    assert_eq!(text, "9");
+}
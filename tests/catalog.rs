@@ -0,0 +1,120 @@
+use book_core::{BookCore, BookCoreError, BookCoreHandle, CatalogQuery};
+use serde_json::json;
+
+const JS: &str = r#"
+const chapter = (id) => ({ id: String(id), name: `第${id}章` })
+const pages = [
+    [{ id: 'v1', name: '第一卷', chapters: [1, 2].map(chapter) }],
+    [
+        { id: 'v1', name: '第一卷', chapters: [2, 3].map(chapter) },
+        { id: 'v2', name: '第二卷', chapters: [4].map(chapter) },
+    ],
+    [{ id: 'v2', name: '第二卷', chapters: [5, 6].map(chapter) }],
+]
+const catalog = ({ bid, page, continuation }) => {
+    if (bid === 'legacy') {
+        return pages[0]
+    }
+    if (bid === 'endless') {
+        return { volumes: [{ id: 'v1', name: '第一卷', chapters: [chapter(page)] }], continuation: page }
+    }
+    if (bid === 'cycle') {
+        return { volumes: pages[0], continuation: 'same' }
+    }
+    const index = continuation ? continuation.index : 0
+    return {
+        volumes: pages[index],
+        continuation: index + 1 < pages.length ? { index: index + 1, page } : null,
+    }
+}
+"#;
+
+#[test]
+fn test_catalog_pages() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let pages = core
+        .catalog_pages("1")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(pages.len(), 3);
+    assert!(pages[0].has_more());
+    assert_eq!(
+        pages[1].continuation,
+        Some(json!({ "index": 2, "page": 2 }))
+    );
+    assert!(!pages[2].has_more());
+    let query = CatalogQuery::new("1");
+    let page = core.get_catalog_page(query.clone()).unwrap();
+    let next = query.next(&page).unwrap();
+    assert_eq!(next.page, 2);
+    assert_eq!(next.continuation, Some(json!({ "index": 1, "page": 1 })));
+}
+
+#[test]
+fn test_volumes_are_merged() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let volumes = core.get_catalog("1".to_string()).unwrap();
+    assert_eq!(volumes.len(), 2);
+    let ids = |index: usize| {
+        volumes[index]
+            .chapters
+            .iter()
+            .map(|chapter| chapter.id.as_str())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(0), ["1", "2", "3"]);
+    assert_eq!(ids(1), ["4", "5", "6"]);
+}
+
+#[test]
+fn test_legacy_and_cycle() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let volumes = core.get_catalog("legacy".to_string()).unwrap();
+    assert_eq!(volumes[0].chapters.len(), 2);
+    assert_eq!(core.catalog_pages("cycle").count(), 2);
+    let volumes = core.get_catalog("cycle".to_string()).unwrap();
+    assert_eq!(volumes[0].chapters.len(), 2);
+}
+
+#[test]
+fn test_handle_streams_pages() {
+    let handle = BookCoreHandle::spawn(JS.to_string()).unwrap();
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut sizes = Vec::new();
+    let volumes = rt
+        .block_on(
+            handle.get_catalog_pages_async("1".to_string(), |page| sizes.push(page.volumes.len())),
+        )
+        .unwrap();
+    assert_eq!(sizes, [1, 2, 1]);
+    assert_eq!(volumes.len(), 2);
+    assert_eq!(volumes[1].chapters.len(), 3);
+}
+
+#[test]
+fn test_too_many_pages() {
+    let mut core = BookCore::builder()
+        .max_list_pages(5)
+        .build(JS.to_string())
+        .unwrap();
+    let result = core.get_catalog("endless".to_string());
+    assert_eq!(result.unwrap_err(), BookCoreError::TooManyPages(5));
+    let pages = core.catalog_pages("endless").collect::<Vec<_>>();
+    assert_eq!(pages.len(), 6);
+    assert!(pages[..5].iter().all(Result::is_ok));
+    assert!(matches!(pages[5], Err(BookCoreError::TooManyPages(5))));
+    let handle =
+        BookCoreHandle::spawn_with(|| BookCore::builder().max_list_pages(3).build(JS.to_string()))
+            .unwrap();
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut count = 0;
+    let result = rt.block_on(handle.get_catalog_pages_async("endless".to_string(), |_| count += 1));
+    assert_eq!(result.unwrap_err(), BookCoreError::TooManyPages(3));
+    assert_eq!(count, 3);
+}
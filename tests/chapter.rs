@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use book_core::{BookCore, BookCoreError, ContentBlock, ExecutionLimits};
+
+const JS: &str = r#"
+const pages = {
+    1: { content: '第一行\n第二行\n第三行', wordCount: 9, continuation: 'p2' },
+    2: { content: '第二行\n第三行\n第四行', wordCount: 3, continuation: { token: 'p3' } },
+    3: { content: '第五行', wordCount: 3 },
+}
+const chapter = ({ cid, continuation, page }) => {
+    if (cid === 'text') {
+        return { id: cid, ...pages[page || 1] }
+    }
+    if (cid === 'cycle') {
+        return { id: cid, content: `第${page || 1}页`, continuation: page === 2 ? 'b' : 'a' }
+    }
+    if (cid === 'repeat') {
+        return { id: cid, content: '同一页', continuation: `${page || 1}` }
+    }
+    if (cid === 'ellipsis') {
+        return page ? { id: cid, content: '……\n她答' } : { id: cid, content: '他说\n……', continuation: 'p2' }
+    }
+    if (cid === 'slow') {
+        const until = Date.now() + 100
+        while (Date.now() < until) {}
+        return { id: cid, content: `第${page || 1}页`, continuation: page === 10 ? null : `${page || 1}` }
+    }
+    if (cid === 'endless') {
+        return { id: cid, content: `第${page || 1}页`, continuation: `${page || 1}` }
+    }
+    if (cid === 'ellipsisBlocks') {
+        const ellipsis = { type: 'paragraph', text: '……' }
+        return page
+            ? { id: cid, blocks: [ellipsis, { type: 'paragraph', text: '她答' }] }
+            : { id: cid, blocks: [{ type: 'paragraph', text: '他说' }, ellipsis], continuation: 'p2' }
+    }
+    if (!continuation) {
+        return {
+            id: cid,
+            blocks: [
+                { type: 'paragraph', text: '第一段' },
+                { type: 'separator' },
+                { type: 'paragraph', text: '第二段' },
+                { type: 'paragraph', text: '第三段' },
+            ],
+            continuation: 'next',
+        }
+    }
+    return {
+        id: cid,
+        blocks: [
+            { type: 'paragraph', text: '第二段' },
+            { type: 'paragraph', text: '第三段' },
+            { type: 'paragraph', text: '第四段' },
+        ],
+    }
+}
+"#;
+
+#[test]
+fn test_pages_are_joined() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let chapter = core
+        .get_chapter("1".to_string(), "text".to_string())
+        .unwrap();
+    assert_eq!(chapter.content, "第一行\n第二行\n第三行\n第四行\n第五行");
+    assert_eq!(chapter.word_count, Some(15));
+    assert!(chapter.continuation.is_none());
+}
+
+#[test]
+fn test_cycles_stop() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let chapter = core
+        .get_chapter("1".to_string(), "cycle".to_string())
+        .unwrap();
+    assert_eq!(chapter.content, "第1页\n第2页\n第3页");
+    let chapter = core
+        .get_chapter("1".to_string(), "repeat".to_string())
+        .unwrap();
+    assert_eq!(chapter.content, "同一页");
+}
+
+#[test]
+fn test_repeated_short_line_is_kept() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let chapter = core
+        .get_chapter("1".to_string(), "ellipsis".to_string())
+        .unwrap();
+    assert_eq!(chapter.content, "他说\n……\n……\n她答");
+}
+
+#[test]
+fn test_timeout_covers_all_pages() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    core.set_limits(ExecutionLimits::new().timeout(Duration::from_millis(300)));
+    let result = core.get_chapter("1".to_string(), "slow".to_string());
+    assert_eq!(result.unwrap_err(), BookCoreError::Timeout);
+}
+
+#[test]
+fn test_too_many_pages() {
+    let mut core = BookCore::builder()
+        .max_chapter_pages(5)
+        .build(JS.to_string())
+        .unwrap();
+    let result = core.get_chapter("1".to_string(), "endless".to_string());
+    assert!(matches!(result, Err(BookCoreError::TooManyPages(5))));
+    core.set_max_chapter_pages(20);
+    let result = core.get_chapter("1".to_string(), "endless".to_string());
+    assert!(matches!(result, Err(BookCoreError::TooManyPages(20))));
+}
+
+#[test]
+fn test_blocks_are_joined() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let chapter = core
+        .get_chapter("1".to_string(), "blocks".to_string())
+        .unwrap();
+    let blocks = chapter.blocks.unwrap();
+    assert_eq!(blocks.len(), 5);
+    assert_eq!(blocks[1], ContentBlock::Separator);
+    assert_eq!(
+        blocks[4],
+        ContentBlock::Paragraph {
+            text: "第四段".to_string(),
+            spans: None
+        }
+    );
+    assert_eq!(chapter.content, "第一段\n第二段\n第三段\n第四段");
+}
+
+#[test]
+fn test_single_repeated_block_is_kept() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let chapter = core
+        .get_chapter("1".to_string(), "ellipsisBlocks".to_string())
+        .unwrap();
+    assert_eq!(chapter.blocks.unwrap().len(), 4);
+    assert_eq!(chapter.content, "他说\n……\n……\n她答");
+}
@@ -0,0 +1,79 @@
+use book_core::{BookCore, BookCoreError, CommentQuery};
+use serde_json::json;
+
+const JS: &str = r#"
+const all = Array.from({ length: 5 }, (_, i) => ({
+    id: String(i),
+    content: `评论 ${i}`,
+    author: `读者${i}`,
+    time: '2024-01-01 12:00',
+    likeCount: i * 10,
+    paragraph: i % 2,
+    replies: i === 0 ? [{ id: 'r0', content: '回复', author: '作者' }] : [],
+    replyCount: i === 0 ? 3 : 0,
+}))
+const comments = ({ bid, cid, paragraph, page, cursor }) => {
+    if (cid === 'legacy') {
+        return page === 1 ? all.slice(0, 2) : []
+    }
+    const items = all.filter((item) => paragraph === undefined || item.paragraph === paragraph)
+    const start = cursor ?? 0
+    const next = start + 2
+    return {
+        items: items.slice(start, next),
+        total: items.length,
+        nextCursor: next < items.length ? next : null,
+        paragraphCounts: { 0: 3, 1: 2 },
+    }
+}
+"#;
+
+#[test]
+fn test_paragraph_comments() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    assert!(core.has_comments());
+    let mut query = CommentQuery::new("1", "2").paragraph(0);
+    let mut ids = Vec::new();
+    loop {
+        let page = core.get_comments(query.clone()).unwrap();
+        assert_eq!(page.total, Some(3));
+        assert_eq!(page.paragraph_counts.get(&0), Some(&3));
+        assert_eq!(page.paragraph_counts.get(&1), Some(&2));
+        ids.extend(page.items.iter().map(|comment| comment.id.clone()));
+        if !page.has_more {
+            break;
+        }
+        query = query.next(&page);
+    }
+    assert_eq!(ids, ["0", "2", "4"]);
+}
+
+#[test]
+fn test_comment_fields() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let page = core.get_comments(CommentQuery::new("1", "2")).unwrap();
+    let comment = &page.items[0];
+    assert_eq!(comment.author.as_deref(), Some("读者0"));
+    assert_eq!(comment.like_count, Some(0));
+    assert_eq!(comment.reply_count, Some(3));
+    let replies = comment.replies.as_ref().unwrap();
+    assert_eq!(replies[0].content, "回复");
+    assert_eq!(page.next_cursor, Some(json!(2)));
+}
+
+#[test]
+fn test_legacy_and_unsupported() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let page = core.get_comments(CommentQuery::new("1", "legacy")).unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert!(page.has_more);
+    assert!(page.paragraph_counts.is_empty());
+    let page = core
+        .get_comments(CommentQuery::new("1", "legacy").page(2))
+        .unwrap();
+    assert!(!page.has_more);
+    let mut core = BookCore::init("const search = () => []".to_string()).unwrap();
+    assert!(!core.has_comments());
+    let err = core.get_comments(CommentQuery::new("1", "2")).unwrap_err();
+    assert_eq!(err, BookCoreError::MissingEntry("comments".to_string()));
+}
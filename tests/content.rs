@@ -0,0 +1,83 @@
+use book_core::{blocks_to_text, BookCore, BookCoreError, ContentBlock};
+
+const JS: &str = r#"
+const metadata = {
+    name: 'comic',
+    uuid: '352561f8-281c-4953-81f7-3772c6285c1c',
+    baseUrl: 'https://example.com/book/',
+    userAgent: 'test',
+    author: 'test',
+    version: '1.0.0',
+}
+const chapter = ({ cid }) => {
+    if (cid === 'text') {
+        return { id: cid, content: '第一段\n第二段' }
+    }
+    if (cid === 'broken') {
+        return { id: cid, blocks: [{ type: 'image', url: '' }] }
+    }
+    return {
+        id: cid,
+        blocks: [
+            { type: 'separator' },
+            { type: 'heading', text: ' 第一章 ', level: 9 },
+            { type: 'paragraph', text: '  第一段  ' },
+            { type: 'paragraph', text: '   ' },
+            { type: 'paragraph', spans: [{ text: '加粗', bold: true }, { text: '与链接', link: 'https://example.com' }] },
+            { type: 'separator' },
+            { type: 'separator' },
+            { type: 'image', url: 'img/1.jpg', headers: { Referer: 'https://example.com' }, alt: '插图' },
+            { type: 'image', url: 'https://cdn.example.com/2.png' },
+            { type: 'separator' },
+        ],
+    }
+}
+"#;
+
+#[test]
+fn test_blocks_are_normalized() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let chapter = core
+        .get_chapter("1".to_string(), "rich".to_string())
+        .unwrap();
+    let blocks = chapter.blocks.unwrap();
+    assert_eq!(blocks.len(), 6);
+    assert_eq!(
+        blocks[0],
+        ContentBlock::Heading {
+            text: "第一章".to_string(),
+            level: Some(6)
+        }
+    );
+    assert!(
+        matches!(&blocks[2], ContentBlock::Paragraph { text, spans: Some(spans) }
+        if text == "加粗与链接" && spans[0].bold && spans[1].link.is_some())
+    );
+    assert_eq!(blocks[3], ContentBlock::Separator);
+    let ContentBlock::Image { url, headers, .. } = &blocks[4] else {
+        panic!("expected an image, got {:?}", blocks[4]);
+    };
+    assert_eq!(url, "https://example.com/book/img/1.jpg");
+    assert_eq!(headers.as_ref().unwrap()["Referer"], "https://example.com");
+    assert_eq!(chapter.content, "第一章\n第一段\n加粗与链接\n插图");
+    assert_eq!(blocks_to_text(&blocks), chapter.content);
+}
+
+#[test]
+fn test_plain_text_chapter() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let chapter = core
+        .get_chapter("1".to_string(), "text".to_string())
+        .unwrap();
+    assert!(chapter.blocks.is_none());
+    assert_eq!(chapter.content, "第一段\n第二段");
+}
+
+#[test]
+fn test_invalid_image() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let err = core
+        .get_chapter("1".to_string(), "broken".to_string())
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::InvalidContent(_)));
+}
@@ -11,7 +11,7 @@ thread_local! {
 fn t() {
     BKS.with(|bks| {
         let mut bks = bks.borrow_mut();
-        *bks = Some(BookCore::init(include_str!("./wk8.js").to_string()));
+        *bks = Some(BookCore::init(include_str!("./wk8.js").to_string()).unwrap());
         let bks = bks.as_mut().unwrap();
         let res = bks.eval::<Value>("test();".to_string());
         match res {
@@ -22,7 +22,7 @@ fn test_crypto_aes256_pkcs7() {
         return aes.decrypt(text);
     }
     "#;
-    let mut core = BookCore::init(js.to_string());
+    let mut core = BookCore::init(js.to_string()).unwrap();
     let res = core.run_action("test".to_string()).unwrap();
     let res = res.as_str().unwrap_or_default();
     assert_eq!(res, "woHoG0gBYIR/Ia8D9FNGyA==");
@@ -51,7 +51,7 @@ fn test_crypto_aes128_pkcs7() {
         return crypto.encrypt(text);
     }
     "#;
-    let mut core = BookCore::init(js.to_string());
+    let mut core = BookCore::init(js.to_string()).unwrap();
     let res = core.run_action("test".to_string()).unwrap();
     let res = res.as_str().unwrap_or_default();
     assert_eq!(res, "f/4/tfxZOUz6mxgRQ6hZ3w==");
@@ -80,7 +80,7 @@ fn test_crypto_aes192_pkcs7() {
         return aes.encrypt(text);
     }
     "#;
-    let mut core = BookCore::init(js.to_string());
+    let mut core = BookCore::init(js.to_string()).unwrap();
     let res = core.run_action("test".to_string()).unwrap();
     let res = res.as_str().unwrap_or_default();
     assert_eq!(res, "VVvt6UCIXhf/6wHutWzroQ==");
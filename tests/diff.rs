@@ -0,0 +1,132 @@
+use book_core::{diff_catalog, diff_catalog_with, CatalogVolume, ChapterEntry};
+use serde_json::json;
+
+fn volumes(value: serde_json::Value) -> Vec<CatalogVolume> {
+    serde_json::from_value(value).unwrap()
+}
+
+fn ids(entries: &[ChapterEntry]) -> Vec<&str> {
+    entries
+        .iter()
+        .map(|entry| entry.chapter.id.as_str())
+        .collect()
+}
+
+fn old() -> Vec<CatalogVolume> {
+    volumes(json!([
+        {
+            "id": "v1",
+            "name": "第一卷",
+            "chapters": [
+                { "id": "1", "name": "第1章 开始", "canRead": true },
+                { "id": "2", "name": "第2章 相遇", "canRead": false },
+                { "id": "3", "name": "第3章 离别", "canRead": false },
+                { "id": "4", "name": "番外" },
+            ]
+        },
+        {
+            "id": "v2",
+            "name": "第二卷",
+            "chapters": [
+                { "id": "6", "name": "第6章 最终决战之前" },
+                { "id": "7", "name": "第7章 风起云涌" },
+            ]
+        }
+    ]))
+}
+
+#[test]
+fn test_catalog_diff() {
+    let new = volumes(json!([
+        {
+            "id": "v1",
+            "name": "第一卷",
+            "chapters": [
+                { "id": "1", "name": "第1章 开始（修）", "canRead": true },
+                { "id": "2", "name": "第2章 相遇", "canRead": true },
+                { "id": "30", "name": "第3章离别", "canRead": false },
+            ]
+        },
+        {
+            "id": "v2",
+            "name": "第二卷",
+            "chapters": [
+                { "id": "60", "name": "第6章 最终决战之前夜" },
+                { "id": "8", "name": "第8章 风起云涌" },
+                { "id": "9", "name": "第9章 新的开始" },
+            ]
+        }
+    ]));
+    let diff = diff_catalog(&old(), &new);
+    assert_eq!(ids(&diff.added), ["8", "9"]);
+    assert_eq!(diff.added[0].volume_id, "v2");
+    assert_eq!(ids(&diff.removed), ["4", "7"]);
+    let renamed: Vec<_> = diff
+        .renamed
+        .iter()
+        .map(|change| {
+            (
+                change.old.chapter.id.as_str(),
+                change.new.chapter.id.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(renamed, [("1", "1"), ("3", "30"), ("6", "60")]);
+    let reissued: Vec<_> = diff
+        .reissued
+        .iter()
+        .map(|change| {
+            (
+                change.old.chapter.id.as_str(),
+                change.new.chapter.id.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(reissued, [("3", "30"), ("6", "60")]);
+    assert_eq!(diff.unlocked.len(), 1);
+    assert_eq!(diff.unlocked[0].new.chapter.id, "2");
+}
+
+#[test]
+fn test_similarity_threshold() {
+    let new = volumes(json!([
+        {
+            "id": "v2",
+            "name": "第二卷",
+            "chapters": [{ "id": "60", "name": "第6章 最终决战之前夜" }]
+        }
+    ]));
+    let diff = diff_catalog_with(&old(), &new, 0.95);
+    assert!(diff.reissued.is_empty());
+    assert_eq!(ids(&diff.added), ["60"]);
+    assert_eq!(diff.removed.len(), 6);
+}
+
+#[test]
+fn test_unchanged_catalog() {
+    let diff = diff_catalog(&old(), &old());
+    assert!(diff.is_empty());
+    let diff = diff_catalog(&[], &old());
+    assert_eq!(diff.added.len(), 6);
+    assert!(diff.removed.is_empty());
+}
+
+#[test]
+fn test_chinese_numerals() {
+    let catalog = |id: &str, name: &str| {
+        volumes(json!([
+            { "id": "v1", "name": "第一卷", "chapters": [{ "id": id, "name": name }] }
+        ]))
+    };
+    let old = catalog("122", "第一百二十二章 重逢");
+    let diff = diff_catalog(&old, &catalog("123", "第一百二十三章 重逢"));
+    assert!(diff.renamed.is_empty());
+    assert_eq!(ids(&diff.added), ["123"]);
+    assert_eq!(ids(&diff.removed), ["122"]);
+    let diff = diff_catalog(&old, &catalog("1220", "第一百二十二章 重逢了"));
+    assert!(diff.added.is_empty());
+    assert_eq!(diff.reissued.len(), 1);
+    let old = catalog("122", "第１２２章 重逢");
+    let diff = diff_catalog(&old, &catalog("123", "第１２３章 重逢"));
+    assert_eq!(ids(&diff.added), ["123"]);
+}
@@ -4,7 +4,7 @@ use serde_json::json;
 #[test]
 fn test_envs() {
     let code = include_str!("./wk8.js");
-    let mut wk8 = BookCore::init(code.to_string());
+    let mut wk8 = BookCore::init(code.to_string()).unwrap();
     wk8.set_envs(json!({
       "name": "zsakvo",
       "age": 18,
@@ -20,11 +20,11 @@ fn test_envs() {
     let res = wk8.get_env("age".to_string()).unwrap();
     println!("{}", res);
     println!("----清除环境变量----");
-    wk8.clear_envs();
+    wk8.clear_envs().unwrap();
     let res = wk8.get_envs().unwrap();
     println!("{}", res);
     println!("----设置性别----");
-    wk8.clear_envs();
+    wk8.clear_envs().unwrap();
     wk8.set_env("gender".to_string(), json!("female")).unwrap();
     let res = wk8.get_envs().unwrap();
     println!("{:?}", res);
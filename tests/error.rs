@@ -0,0 +1,107 @@
+use book_core::{BookCore, BookCoreError};
+
+#[test]
+fn test_syntax_error() {
+    let js = r#"
+    const search = (params) => {
+    "#;
+    let err = BookCore::init(js.to_string()).unwrap_err();
+    assert!(matches!(err, BookCoreError::Syntax(_)));
+}
+
+#[test]
+fn test_exception() {
+    let js = r#"
+    function test(){
+        throw new TypeError("boom");
+    }
+    "#;
+    let mut core = BookCore::init(js.to_string()).unwrap();
+    let err = core.run_action("test".to_string()).unwrap_err();
+    assert_eq!(
+        err,
+        BookCoreError::Exception {
+            name: "TypeError".to_string(),
+            message: "boom".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_path() {
+    let js = r#"
+    const detail = ({ bid }) => ({
+        id: bid,
+        name: "test",
+        latestChapter: { id: 1, name: "第一章" },
+    })
+    "#;
+    let mut core = BookCore::init(js.to_string()).unwrap();
+    match core.get_book_detail("1".to_string()).unwrap_err() {
+        BookCoreError::Deserialize { path, .. } => assert_eq!(path, "latestChapter.id"),
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn test_undefined_field() {
+    let js = r#"
+    const detail = ({ bid }) => ({
+        id: bid,
+        name: "test",
+        author: undefined,
+        cover: undefined,
+    })
+    "#;
+    let mut core = BookCore::init(js.to_string()).unwrap();
+    let detail = core.get_book_detail("1".to_string()).unwrap();
+    assert_eq!(detail.author, None);
+}
+
+#[test]
+fn test_missing_entry() {
+    let mut core = BookCore::init("const metadata = {}".to_string()).unwrap();
+    let err = core.get_catalog("1".to_string()).unwrap_err();
+    assert_eq!(err, BookCoreError::MissingEntry("catalog".to_string()));
+}
+
+#[test]
+fn test_native_errors_are_catchable() {
+    let js = r#"
+    const attempt = (name, f) => {
+        try {
+            f()
+            return `${name}: ok`
+        } catch (e) {
+            return `${name}: thrown`
+        }
+    }
+    const aes = { cipherMode: 'ofb', aesType: 'aes128', paddingType: 'pkcs7', encoding: 'base64', key: '0123456789abcdef' }
+    function probe() {
+        return [
+            attempt('xml', () => xml2Json('<a')),
+            attempt('randString', () => randString()),
+            attempt('randStringText', () => randString('x')),
+            attempt('toQuery', () => ({ a: Symbol('a') }).toQuery()),
+            attempt('hmac', () => new Hmac('sha256')),
+            attempt('aesKey', () => new Aes({ ...aes, aesType: 'aes256', key: 'short' })),
+            attempt('aesBase64', () => new Aes(aes).decrypt('@@@')),
+        ]
+    }
+    "#;
+    let mut core = BookCore::init(js.to_string()).unwrap();
+    let results = core.run_action("probe".to_string()).unwrap();
+    // 截断的 XML 是否报错取决于解析器，这里只要求不会 panic
+    assert!(results[0].as_str().unwrap().starts_with("xml: "));
+    assert_eq!(
+        results.as_array().unwrap()[1..],
+        [
+            "randString: thrown",
+            "randStringText: thrown",
+            "toQuery: thrown",
+            "hmac: thrown",
+            "aesKey: thrown",
+            "aesBase64: thrown",
+        ]
+    );
+}
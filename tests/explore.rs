@@ -0,0 +1,67 @@
+use book_core::{BookCore, BookCoreError, ExploreQuery, ExploreSectionType, FilterValue};
+
+const JS: &str = r#"
+const exploreSections = [
+    { id: 'rank', title: '排行榜', sectionType: 'rank', filters: [
+        { id: 'period', label: '周期', filterType: 'select', options: [
+            { label: '周榜', value: 'week' }, { label: '月榜', value: 'month' },
+        ] },
+    ] },
+    { id: 'new', title: '新书', sectionType: 'latest' },
+    { id: 'editor', title: '编辑推荐', sectionType: 'editorPick' },
+]
+const explore = ({ sectionId, page, filters }) => {
+    if (page > 2) {
+        return []
+    }
+    const period = filters?.period ?? 'week'
+    return [{ id: `${sectionId}-${page}`, name: period }]
+}
+"#;
+
+#[test]
+fn test_sections() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    assert!(core.has_explore());
+    let sections = core.get_explore_sections().unwrap();
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0].section_type, ExploreSectionType::Rank);
+    assert_eq!(sections[2].section_type, ExploreSectionType::Other);
+    assert!(sections[1].filters.is_none());
+}
+
+#[test]
+fn test_paged_explore() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let page = core.get_explore("new".to_string(), 2).unwrap();
+    assert_eq!(page.items[0].id, "new-2");
+    assert!(page.has_more);
+    let page = core.get_explore("new".to_string(), 3).unwrap();
+    assert!(page.items.is_empty());
+    assert!(!page.has_more);
+    let page = core
+        .explore(
+            ExploreQuery::new("rank").filter("period", FilterValue::Single("month".to_string())),
+        )
+        .unwrap();
+    assert_eq!(page.items[0].name, "month");
+    let err = core
+        .explore(
+            ExploreQuery::new("new").filter("period", FilterValue::Single("month".to_string())),
+        )
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::InvalidFilter { .. }));
+    let err = core.get_explore("missing".to_string(), 1).unwrap_err();
+    assert_eq!(err, BookCoreError::SectionNotFound("missing".to_string()));
+}
+
+#[test]
+fn test_unsupported() {
+    let mut core = BookCore::init("const search = () => []".to_string()).unwrap();
+    assert!(!core.has_explore());
+    assert!(core.get_explore_sections().unwrap().is_empty());
+    assert!(matches!(
+        core.get_explore("rank".to_string(), 1).unwrap_err(),
+        BookCoreError::SectionNotFound(_)
+    ));
+}
@@ -0,0 +1,305 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    sync::mpsc,
+    thread,
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use book_core::{BookCore, BookCoreError, FormOutcome};
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Map, Value};
+
+const JS: &str = r#"
+const forms = [
+    {
+        id: 'login',
+        title: '用户登录',
+        fields: [
+            { fieldType: 'input', field: 'username', label: '用户名' },
+            { fieldType: 'input', field: 'password', label: '密码', password: true, persist: false },
+            { fieldType: 'checkbox', field: 'remember', label: '记住我' },
+            { fieldType: 'button', field: 'login', label: '登录' },
+        ],
+    },
+    {
+        title: '设置',
+        fields: [{ fieldType: 'input', field: 'cookies', label: 'Cookies' }],
+    },
+]
+const login = ({ username, password }) => {
+    if (getEnv('username') !== username || getEnv('password') !== password) {
+        throw new Error('values were not stored before the handler ran')
+    }
+    if (username === 'taken') {
+        return { errors: { username: '用户名不存在' } }
+    }
+    if (password === 'wrong') {
+        return { success: false, message: '密码错误' }
+    }
+    if (password === '2fa') {
+        return {
+            envs: { pending: true },
+            form: { id: 'otp', title: '二次验证', handler: 'verify', fields: [
+                { fieldType: 'input', field: 'code', label: '验证码' },
+            ] },
+        }
+    }
+    return { envs: { token: `${username}-token` }, message: '登录成功' }
+}
+const verify = ({ code }) => ({ success: code === '123456', envs: { pending: false } })
+"#;
+
+fn values(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn test_submit_success() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let outcome = core
+        .submit_form(
+            "login",
+            values(json!({ "username": "zsakvo", "password": "pwd", "remember": true })),
+        )
+        .unwrap();
+    assert_eq!(
+        outcome,
+        FormOutcome::Success {
+            message: Some("登录成功".to_string())
+        }
+    );
+    assert_eq!(
+        core.get_env("token".to_string()).unwrap(),
+        json!("zsakvo-token")
+    );
+    assert_eq!(core.get_env("remember".to_string()).unwrap(), json!(true));
+    assert_eq!(
+        core.get_env("username".to_string()).unwrap(),
+        json!("zsakvo")
+    );
+    assert_eq!(core.get_env("password".to_string()).unwrap(), Value::Null);
+}
+
+#[test]
+fn test_submit_by_title_without_handler() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let outcome = core
+        .submit_form("设置", values(json!({ "cookies": "a=1" })))
+        .unwrap();
+    assert_eq!(outcome, FormOutcome::Success { message: None });
+    assert_eq!(core.get_env("cookies".to_string()).unwrap(), json!("a=1"));
+    let err = core.submit_form("missing", Map::new()).unwrap_err();
+    assert_eq!(err, BookCoreError::FormNotFound("missing".to_string()));
+}
+
+#[test]
+fn test_field_errors() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let outcome = core
+        .submit_form(
+            "login",
+            values(json!({ "username": 1, "remember": "yes", "extra": "x" })),
+        )
+        .unwrap();
+    let FormOutcome::FieldErrors(errors) = outcome else {
+        panic!("expected field errors, got {:?}", outcome);
+    };
+    assert_eq!(
+        errors.keys().collect::<Vec<_>>(),
+        ["extra", "remember", "username"]
+    );
+    assert_eq!(core.get_env("username".to_string()).unwrap(), Value::Null);
+    let outcome = core
+        .submit_form(
+            "login",
+            values(json!({ "username": "taken", "password": "pwd" })),
+        )
+        .unwrap();
+    assert!(matches!(outcome, FormOutcome::FieldErrors(errors) if errors.contains_key("username")));
+}
+
+#[test]
+fn test_failed_and_follow_up() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let outcome = core
+        .submit_form(
+            "login",
+            values(json!({ "username": "a", "password": "wrong" })),
+        )
+        .unwrap();
+    assert_eq!(
+        outcome,
+        FormOutcome::Failed {
+            message: Some("密码错误".to_string())
+        }
+    );
+    assert_eq!(core.get_env("password".to_string()).unwrap(), Value::Null);
+    let outcome = core
+        .submit_form(
+            "login",
+            values(json!({ "username": "a", "password": "2fa" })),
+        )
+        .unwrap();
+    let FormOutcome::NextForm(form) = outcome else {
+        panic!("expected a follow-up form, got {:?}", outcome);
+    };
+    assert_eq!(form.key(), "otp");
+    assert_eq!(core.get_env("pending".to_string()).unwrap(), json!(true));
+    let outcome = core
+        .submit(&form, values(json!({ "code": "123456" })))
+        .unwrap();
+    assert_eq!(outcome, FormOutcome::Success { message: None });
+    assert_eq!(core.get_env("pending".to_string()).unwrap(), json!(false));
+}
+
+const SCHEMA_JS: &str = r#"
+const forms = [
+    {
+        id: 'account',
+        title: '账号',
+        fields: [
+            {
+                fieldType: 'select',
+                field: 'mode',
+                label: '登录方式',
+                options: [{ label: '密码', value: 'password' }, { label: 'Cookies', value: 'cookies' }],
+                default: 'password',
+            },
+            { fieldType: 'input', field: 'phone', label: '手机号', required: true, pattern: '1\\d{10}',
+              helpText: '11 位手机号', visibleWhen: [{ field: 'mode', equals: 'password' }] },
+            { fieldType: 'input', field: 'cookies', label: 'Cookies', required: true,
+              visibleWhen: [{ field: 'mode', in: ['cookies'] }] },
+            { fieldType: 'checkbox', field: 'sync', label: '同步书架', default: true },
+        ],
+    },
+]
+"#;
+
+#[test]
+fn test_schema() {
+    let mut core = BookCore::init(SCHEMA_JS.to_string()).unwrap();
+    let form = core.get_forms().unwrap().remove(0);
+    let mode = &form.fields[0];
+    assert_eq!(mode.options.as_ref().unwrap()[1].value, "cookies");
+    assert_eq!(mode.default, Some(json!("password")));
+    let phone = &form.fields[1];
+    assert_eq!(phone.required, Some(true));
+    assert_eq!(phone.help_text.as_deref(), Some("11 位手机号"));
+    assert!(phone.is_visible(&values(json!({ "mode": "password" }))));
+    assert!(!phone.is_visible(&values(json!({ "mode": "cookies" }))));
+}
+
+#[test]
+fn test_schema_validation() {
+    let mut core = BookCore::init(SCHEMA_JS.to_string()).unwrap();
+    let form = core.get_forms().unwrap().remove(0);
+    let normalized = form.normalize(&values(json!({ "cookies": "a=1" })));
+    assert_eq!(
+        normalized,
+        values(json!({ "mode": "password", "sync": true }))
+    );
+    let errors = form.validate(&normalized);
+    assert_eq!(errors.get("phone").map(String::as_str), Some("required"));
+    let errors = form.validate(&values(json!({ "mode": "qr", "phone": "123" })));
+    assert!(errors.contains_key("mode"));
+    assert!(errors.contains_key("phone"));
+    let errors = form.validate(&form.normalize(&values(json!({ "phone": "13800000000" }))));
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_submit_with_conditions() {
+    let mut core = BookCore::init(SCHEMA_JS.to_string()).unwrap();
+    let outcome = core
+        .submit_form("account", values(json!({ "mode": "cookies" })))
+        .unwrap();
+    assert!(matches!(outcome, FormOutcome::FieldErrors(errors) if errors.contains_key("cookies")));
+    let outcome = core
+        .submit_form(
+            "account",
+            values(json!({ "mode": "cookies", "cookies": "a=1", "phone": "bad" })),
+        )
+        .unwrap();
+    assert_eq!(outcome, FormOutcome::Success { message: None });
+    assert_eq!(core.get_env("cookies".to_string()).unwrap(), json!("a=1"));
+    assert_eq!(core.get_env("sync".to_string()).unwrap(), json!(true));
+    assert_eq!(core.get_env("phone".to_string()).unwrap(), Value::Null);
+}
+
+/// 依次以 `bodies` 响应请求，并把每个请求的正文发回测试线程
+fn serve(bodies: Vec<&'static str>) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for body in bodies {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let mut request = Vec::new();
+            let header_end = loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                if let Some(index) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break index + 4;
+                }
+            };
+            let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+            let length: usize = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .map(|value| value.trim().parse().unwrap())
+                .unwrap_or(0);
+            while request.len() < header_end + length {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            sender
+                .send(String::from_utf8_lossy(&request[header_end..]).into_owned())
+                .unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nSet-Cookie: token=wk8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (format!("http://{}", addr), receiver)
+}
+
+#[test]
+fn test_wk8_login_form() {
+    let (url, requests) = serve(vec!["1", "0"]);
+    let code = include_str!("./wk8.js").replace("http://app.wenku8.com/android.php", &url);
+    let mut core = BookCore::init(code).unwrap();
+    // 处理函数只读取 __ENVS__，取值必须在调用前写入
+    let outcome = core
+        .submit_form(
+            "用户登录",
+            values(json!({ "username": "zsakvo", "password": "pwd" })),
+        )
+        .unwrap();
+    assert_eq!(outcome, FormOutcome::Success { message: None });
+    let body = requests.recv().unwrap();
+    let request = body
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("request="))
+        .unwrap();
+    let request = percent_decode_str(request).decode_utf8().unwrap();
+    assert_eq!(
+        BASE64.decode(request.as_bytes()).unwrap(),
+        b"action=login&username=zsakvo&password=pwd"
+    );
+    assert_eq!(
+        core.get_env("cookies".to_string()).unwrap(),
+        json!("token=wk8")
+    );
+    // 直接填写的 Cookies 虽然输入时隐藏，仍然要保存
+    let outcome = core
+        .submit_form("用户登录", values(json!({ "cookies": "a=1" })))
+        .unwrap();
+    assert_eq!(outcome, FormOutcome::Success { message: None });
+    requests.recv().unwrap();
+    assert_eq!(core.get_env("cookies".to_string()).unwrap(), json!("a=1"));
+}
@@ -0,0 +1,60 @@
+use std::thread;
+
+use book_core::{BookCoreError, BookCoreHandle};
+use serde_json::json;
+
+const JS: &str = r#"
+const metadata = {
+  name: 'handle',
+  uuid: '8d3c0f55-8b36-4a3e-9c1f-0c2d3a4b5c6d',
+  baseUrl: 'http://localhost',
+  userAgent: 'test',
+  author: 'test',
+  version: '1.0.0',
+}
+const search = ({ key }) => [{ id: key, name: getEnv('prefix') + key }]
+"#;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_handle_is_send_sync() {
+    assert_send_sync::<BookCoreHandle>();
+}
+
+#[test]
+fn test_shared_across_threads() {
+    let handle = BookCoreHandle::spawn(JS.to_string()).unwrap();
+    handle
+        .set_env("prefix".to_string(), json!("book-"))
+        .unwrap();
+    let workers: Vec<_> = (0..4)
+        .map(|i| {
+            let handle = handle.clone();
+            thread::spawn(move || handle.search_books(i.to_string(), 1, 10).unwrap())
+        })
+        .collect();
+    for (i, worker) in workers.into_iter().enumerate() {
+        let books = worker.join().unwrap();
+        assert_eq!(books[0].name, format!("book-{}", i));
+    }
+}
+
+#[test]
+fn test_async_calls() {
+    let handle = BookCoreHandle::spawn(JS.to_string()).unwrap();
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let metadata = rt
+        .block_on(async { handle.get_metadata_async().await })
+        .unwrap();
+    assert_eq!(metadata.name, "handle");
+}
+
+#[test]
+fn test_init_error() {
+    let err = BookCoreHandle::spawn("const = 1".to_string()).unwrap_err();
+    assert!(matches!(err, BookCoreError::Syntax(_)));
+}
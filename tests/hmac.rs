@@ -15,7 +15,7 @@ fn test_hmac() {
         return res;
     }
     "#;
-    let mut core = BookCore::init(js.to_string());
+    let mut core = BookCore::init(js.to_string()).unwrap();
     let res = core.run_action("test".to_string()).unwrap();
     let res = res.as_str().unwrap_or_default();
     assert_eq!(res, "khEfgERKXw9rSxVY7UyoUA==");
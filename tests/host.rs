@@ -0,0 +1,89 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use book_core::{BookCore, BookCoreError, HostModule};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const JS: &str = r#"
+const add = ({ a, b }) => sum(a, b)
+const captcha = ({ image }) => app.solveCaptcha({ image, retries: 2 })
+const device = () => app.deviceId()
+const guarded = () => { try { app.fail() } catch (e) { return `${e.name}: ${e.message}` } }
+const unguarded = () => app.fail()
+const wrongArgs = () => sum('a', 'b')
+"#;
+
+#[derive(Debug, Deserialize)]
+struct Captcha {
+    image: String,
+    retries: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct Solved {
+    code: String,
+    attempts: u32,
+}
+
+fn core() -> BookCore {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    core.register_function("sum", |(a, b): (i64, i64)| Ok(a + b))
+        .unwrap();
+    let calls = Arc::new(AtomicU32::new(0));
+    let module = HostModule::new("app")
+        .function("deviceId", move |_: Vec<Value>| {
+            let calls = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(format!("device-{}", calls))
+        })
+        .function("solveCaptcha", |(captcha,): (Captcha,)| {
+            Ok(Solved {
+                code: captcha.image.to_uppercase(),
+                attempts: captcha.retries,
+            })
+        })
+        .function("fail", |_: Vec<Value>| -> Result<(), BookCoreError> {
+            Err(BookCoreError::Exception {
+                name: "CaptchaError".to_string(),
+                message: "unsolvable".to_string(),
+            })
+        });
+    core.register_module(module).unwrap();
+    core
+}
+
+#[test]
+fn test_global_function() {
+    let mut core = core();
+    let sum: i64 = core.invoke("add", json!({ "a": 1, "b": 2 })).unwrap();
+    assert_eq!(sum, 3);
+    let err = core.invoke::<Value>("wrongArgs", Value::Null).unwrap_err();
+    assert!(matches!(err, BookCoreError::Exception { name, .. } if name == "TypeError"));
+}
+
+#[test]
+fn test_module() {
+    let mut core = core();
+    let solved: Value = core.invoke("captcha", json!({ "image": "abc" })).unwrap();
+    assert_eq!(solved, json!({ "code": "ABC", "attempts": 2 }));
+    let first: String = core.invoke("device", Value::Null).unwrap();
+    let second: String = core.invoke("device", Value::Null).unwrap();
+    assert_eq!((first.as_str(), second.as_str()), ("device-1", "device-2"));
+}
+
+#[test]
+fn test_errors_propagate() {
+    let mut core = core();
+    let message: String = core.invoke("guarded", Value::Null).unwrap();
+    assert_eq!(message, "CaptchaError: unsolvable");
+    let err = core.invoke::<Value>("unguarded", Value::Null).unwrap_err();
+    assert_eq!(
+        err,
+        BookCoreError::Exception {
+            name: "CaptchaError".to_string(),
+            message: "unsolvable".to_string(),
+        }
+    );
+}
@@ -0,0 +1,75 @@
+use book_core::{BookCore, BookCoreError};
+use serde::Deserialize;
+use serde_json::json;
+
+const JS: &str = r#"
+let injected = false
+const search = ({ key, page, count }) => [{ id: `${page}-${count}`, name: key }]
+const detail = ({ bid }) => ({ id: bid, name: bid })
+const chapter = ({ bid, cid }) => ({ id: cid, content: bid })
+const wasInjected = () => injected
+const login = ({ username, password }) => ({ token: `${username}:${password}`, length: password.length })
+"#;
+
+#[derive(Debug, Deserialize)]
+struct Login {
+    token: String,
+    length: usize,
+}
+
+#[test]
+fn test_special_characters() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let keys = [
+        "it's",
+        "line\nbreak",
+        "back\\slash",
+        "'}); injected = true; ({'",
+        "${injected = true}",
+    ];
+    for key in keys {
+        let books = core.search_books(key.to_string(), 2, 20).unwrap();
+        assert_eq!(books[0].id, "2-20");
+        assert_eq!(books[0].name, key);
+        let chapter = core.get_chapter(key.to_string(), "1".to_string()).unwrap();
+        assert_eq!(chapter.content, key);
+    }
+    let injected: bool = core.invoke("wasInjected", serde_json::Value::Null).unwrap();
+    assert!(!injected);
+}
+
+#[test]
+fn test_set_env() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let value = json!({ "cookie": "a='1'; b=\"2\"", "ids": [1, 2] });
+    core.set_env("it's".to_string(), value.clone()).unwrap();
+    assert_eq!(core.get_env("it's".to_string()).unwrap(), value);
+}
+
+#[test]
+fn test_invoke_custom_entry() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let login: Login = core
+        .invoke(
+            "login",
+            json!({ "username": "zsakvo", "password": "p'w\"d" }),
+        )
+        .unwrap();
+    assert_eq!(login.token, "zsakvo:p'w\"d");
+    assert_eq!(login.length, 6);
+}
+
+#[test]
+fn test_invoke_rejects_expression() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let err = core
+        .invoke::<bool>("wasInjected(), injected = true", serde_json::Value::Null)
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::MissingEntry(_)));
+    let err = core
+        .run_action("(() => { injected = true })".to_string())
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::MissingEntry(_)));
+    let injected: bool = core.invoke("wasInjected", serde_json::Value::Null).unwrap();
+    assert!(!injected);
+}
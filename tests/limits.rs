@@ -0,0 +1,107 @@
+use std::{
+    io::Read,
+    net::TcpListener,
+    thread,
+    time::{Duration, Instant},
+};
+
+use book_core::{BookCore, BookCoreError, ExecutionLimits};
+use serde_json::{json, Value};
+
+const JS: &str = r#"
+const spin = () => { let i = 0; while (true) { i++ } }
+const spinFor = ({ n }) => { let i = 0; while (i < n) { i++ } return i }
+const recurse = () => recurse()
+const guarded = () => { try { while (true) {} } catch (e) { return 'caught' } }
+const count = () => { let i = 0; for (let j = 0; j < 100; j++) { i++ } return i }
+const search = ({ key }) => {
+    const res = JReqwest.get(key, {})
+    return [{ id: String(res.status), name: res.body }]
+}
+"#;
+
+/// 接受连接后一直不返回响应
+fn serve_never() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 4096];
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+        }
+    });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn test_loop_limit() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    core.set_limits(ExecutionLimits::new().loop_iterations(1_000));
+    let err = core.invoke::<Value>("spin", Value::Null).unwrap_err();
+    assert!(matches!(err, BookCoreError::LimitExceeded(_)));
+    let err = core.invoke::<Value>("guarded", Value::Null).unwrap_err();
+    assert!(matches!(err, BookCoreError::LimitExceeded(_)));
+    let count: u32 = core.invoke("count", Value::Null).unwrap();
+    assert_eq!(count, 100);
+}
+
+#[test]
+fn test_recursion_limit() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    core.set_limits(ExecutionLimits::new().recursion(64));
+    let err = core.invoke::<Value>("recurse", Value::Null).unwrap_err();
+    assert!(matches!(err, BookCoreError::LimitExceeded(_)));
+}
+
+#[test]
+fn test_per_call_limits() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let err = core
+        .with_limits(ExecutionLimits::new().loop_iterations(1_000), |core| {
+            core.invoke::<Value>("spin", Value::Null)
+        })
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::LimitExceeded(_)));
+    assert_eq!(core.limits(), ExecutionLimits::default());
+}
+
+#[test]
+fn test_timeout_stops_pure_js() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    // 超时对纯 JS 只是每毫秒 5,000 次的循环预算，200ms 折算 100 万次；
+    // 用本机跑同样次数循环的耗时作为基准，而不是墙钟时间
+    let start = Instant::now();
+    let spun: u32 = core.invoke("spinFor", json!({ "n": 100_000 })).unwrap();
+    assert_eq!(spun, 100_000);
+    let budget = start.elapsed() * 10 * 3 + Duration::from_millis(200);
+    core.set_limits(ExecutionLimits::new().timeout(Duration::from_millis(200)));
+    for func in ["spin", "guarded"] {
+        let start = Instant::now();
+        let err = core.invoke::<Value>(func, Value::Null).unwrap_err();
+        assert_eq!(err, BookCoreError::Timeout);
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed < budget,
+            "{} took {:?}, budget {:?}",
+            func,
+            elapsed,
+            budget
+        );
+    }
+    let count: u32 = core.invoke("count", Value::Null).unwrap();
+    assert_eq!(count, 100);
+}
+
+#[test]
+fn test_timeout_covers_request() {
+    let url = serve_never();
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    core.set_limits(ExecutionLimits::new().timeout(Duration::from_millis(300)));
+    let start = Instant::now();
+    let err = core.search_books(url, 1, 10).unwrap_err();
+    assert_eq!(err, BookCoreError::Timeout);
+    assert!(start.elapsed() < Duration::from_secs(3));
+}
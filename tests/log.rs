@@ -38,8 +38,8 @@ impl Logger for CustLogger {
 #[test]
 fn test() {
     let code = include_str!("./wk8.js");
-    let mut wk8 = BookCore::init(code.to_string());
-    wk8.regist_cust_logger(CustLogger);
+    let mut wk8 = BookCore::init(code.to_string()).unwrap();
+    wk8.regist_cust_logger(CustLogger).unwrap();
     let res = wk8.eval::<Value>("test();".to_string());
     println!("{}", res.unwrap());
 }
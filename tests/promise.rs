@@ -0,0 +1,49 @@
+use book_core::{BookCore, BookCoreError};
+
+const JS: &str = r#"
+const forms = Promise.resolve([{ title: "登录", fields: [] }])
+const fetchName = async (id) => `book-${id}`
+async function search({ key }) {
+    const name = await fetchName(key)
+    return [{ id: key, name }]
+}
+async function detail({ bid }) {
+    await null
+    throw new Error(`book ${bid} not found`)
+}
+const catalog = () => new Promise(() => {})
+async function test() {
+    return await Promise.all([1, 2].map(async (n) => n * 2))
+}
+"#;
+
+#[test]
+fn test_async_entry() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let books = core.search_books("42".to_string(), 1, 10).unwrap();
+    assert_eq!(books[0].name, "book-42");
+    let forms = core.get_forms().unwrap();
+    assert_eq!(forms[0].title, "登录");
+    let res = core.run_action("test".to_string()).unwrap();
+    assert_eq!(res, serde_json::json!([2, 4]));
+}
+
+#[test]
+fn test_rejected_promise() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let err = core.get_book_detail("7".to_string()).unwrap_err();
+    assert_eq!(
+        err,
+        BookCoreError::Exception {
+            name: "Error".to_string(),
+            message: "book 7 not found".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_pending_promise() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let err = core.get_catalog("1".to_string()).unwrap_err();
+    assert_eq!(err, BookCoreError::PromisePending);
+}
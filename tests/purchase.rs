@@ -0,0 +1,107 @@
+use book_core::{BookCore, BookCoreHandle, LoginRequired, PurchaseResult};
+use serde_json::json;
+
+const JS: &str = r#"
+let remaining = 100
+const owned = new Set(['1'])
+const catalog = () => [{
+    id: 'v1',
+    name: '正文',
+    chapters: ['1', '2', '3'].map((id) => ({
+        id,
+        name: `第${id}章`,
+        isVip: true,
+        canRead: owned.has(id),
+        price: { amount: id === '3' ? 200 : 30, currency: '书币' },
+    })),
+}]
+const price = ({ cids }) => Object.fromEntries(
+    cids.filter((cid) => !owned.has(cid)).map((cid) => [cid, { amount: cid === '3' ? 200 : 30, currency: '书币' }])
+)
+const balance = () => getEnv('token')
+    ? { amount: remaining, currency: '书币' }
+    : { type: 'loginRequired', form: 'login', message: '请先登录' }
+const buy = ({ cids }) => {
+    if (cids.every((cid) => owned.has(cid))) {
+        return { type: 'alreadyOwned' }
+    }
+    const cost = cids.filter((cid) => !owned.has(cid)).length * 30
+    remaining -= cost
+    cids.forEach((cid) => owned.add(cid))
+    return { type: 'bought', balance: remaining }
+}
+"#;
+
+#[test]
+fn test_catalog_price() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let volumes = core.get_catalog("1".to_string()).unwrap();
+    let price = volumes[0].chapters[2].price.as_ref().unwrap();
+    assert_eq!(price.amount, 200);
+    assert_eq!(price.currency.as_deref(), Some("书币"));
+}
+
+#[test]
+fn test_buy_with_confirmation() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    assert!(core.has_purchase());
+    let cids = vec!["1".to_string(), "2".to_string()];
+    let result = core
+        .buy("1".to_string(), cids.clone(), |_| {
+            panic!("should not ask for confirmation")
+        })
+        .unwrap();
+    assert_eq!(
+        result,
+        PurchaseResult::LoginRequired(LoginRequired {
+            form: Some("login".to_string()),
+            message: Some("请先登录".to_string()),
+        })
+    );
+    core.set_env("token".to_string(), json!("token")).unwrap();
+    assert_eq!(core.get_balance().unwrap().amount, 100);
+    let result = core
+        .buy("1".to_string(), cids.clone(), |quote| {
+            assert_eq!(quote.total, Some(30));
+            assert_eq!(quote.prices["2"].amount, 30);
+            false
+        })
+        .unwrap();
+    assert_eq!(result, PurchaseResult::Declined);
+    let result = core.buy("1".to_string(), cids.clone(), |_| true).unwrap();
+    assert_eq!(result, PurchaseResult::Bought { balance: Some(70) });
+    let result = core.buy("1".to_string(), cids, |_| true).unwrap();
+    assert_eq!(result, PurchaseResult::AlreadyOwned);
+}
+
+#[test]
+fn test_insufficient_balance() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    core.set_env("token".to_string(), json!("token")).unwrap();
+    let result = core
+        .buy("1".to_string(), vec!["3".to_string()], |_| {
+            panic!("should not ask for confirmation")
+        })
+        .unwrap();
+    assert_eq!(
+        result,
+        PurchaseResult::InsufficientBalance {
+            required: Some(200),
+            balance: Some(100)
+        }
+    );
+    let handle = BookCoreHandle::spawn(JS.to_string()).unwrap();
+    handle.set_env("token".to_string(), json!("token")).unwrap();
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let result = rt
+        .block_on(
+            handle.buy_async("1".to_string(), vec!["2".to_string()], |quote| {
+                quote.total == Some(30)
+            }),
+        )
+        .unwrap();
+    assert_eq!(result, PurchaseResult::Bought { balance: Some(70) });
+}
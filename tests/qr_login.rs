@@ -0,0 +1,109 @@
+use std::{thread, time::Duration};
+
+use book_core::{BookCore, BookCoreError, BookCoreHandle, CancellationToken, QrLoginStatus};
+use serde_json::json;
+
+const JS: &str = r#"
+let polls = 0
+const qrLogin = () => ({ payload: 'https://example.com/qr?id=42', handle: { id: 42 }, interval: 0 })
+const qrLoginStatus = ({ id }) => {
+    polls++
+    if (polls < 3) {
+        return { status: 'waiting' }
+    }
+    if (polls < 5) {
+        return { status: 'scanned' }
+    }
+    return { status: 'confirmed', envs: { token: `token-${id}` } }
+}
+"#;
+
+const SLOW_JS: &str = r#"
+const qrLogin = () => ({ payload: 'slow', handle: null, interval: 60000, expiresIn: 120 })
+const qrLoginStatus = () => ({ status: 'waiting' })
+"#;
+
+#[test]
+fn test_render() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    assert!(core.has_qr_login());
+    let login = core.start_qr_login().unwrap();
+    assert_eq!(login.handle, json!({ "id": 42 }));
+    assert_eq!(login.interval, Duration::from_millis(100));
+    let png = login.to_png(256).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    let svg = login.to_svg().unwrap();
+    assert!(svg.contains("<svg"));
+}
+
+#[test]
+fn test_poll_until_confirmed() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let login = core.start_qr_login().unwrap();
+    assert_eq!(core.poll_qr_login(&login).unwrap(), QrLoginStatus::Waiting);
+    let mut seen = Vec::new();
+    let status = core
+        .wait_qr_login(&login, &CancellationToken::new(), |status| {
+            seen.push(status)
+        })
+        .unwrap();
+    assert_eq!(status, QrLoginStatus::Confirmed);
+    assert_eq!(
+        seen,
+        [
+            QrLoginStatus::Waiting,
+            QrLoginStatus::Scanned,
+            QrLoginStatus::Confirmed
+        ]
+    );
+    assert_eq!(
+        core.get_env("token".to_string()).unwrap(),
+        json!("token-42")
+    );
+}
+
+#[test]
+fn test_expired() {
+    let mut core = BookCore::init(SLOW_JS.to_string()).unwrap();
+    let mut login = core.start_qr_login().unwrap();
+    assert!(!login.is_expired());
+    login.expires_at = Some(std::time::Instant::now());
+    assert_eq!(core.poll_qr_login(&login).unwrap(), QrLoginStatus::Expired);
+}
+
+#[test]
+fn test_huge_interval() {
+    let mut core = BookCore::init(SLOW_JS.to_string()).unwrap();
+    let mut login = core.start_qr_login().unwrap();
+    login.interval = Duration::MAX;
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        canceller.cancel();
+    });
+    let err = core.wait_qr_login(&login, &token, |_| {}).unwrap_err();
+    assert_eq!(err, BookCoreError::Cancelled);
+}
+
+#[test]
+fn test_cancel_wait() {
+    let handle = BookCoreHandle::spawn(SLOW_JS.to_string()).unwrap();
+    let login = handle.start_qr_login().unwrap();
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        canceller.cancel();
+    });
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let err = rt
+        .block_on(handle.wait_qr_login_async(login, token, |_| {}))
+        .unwrap_err();
+    assert_eq!(err, BookCoreError::Cancelled);
+    let mut core = BookCore::init("const search = () => []".to_string()).unwrap();
+    assert!(!core.has_qr_login());
+}
@@ -0,0 +1,116 @@
+use book_core::{BookCore, BookCoreError, ExecutionLimits, SourceRegistry};
+use serde_json::Value;
+
+const UUID: &str = "352561f8-281c-4953-81f7-3772c6285c1c";
+
+fn source(uuid: &str, name: &str, version: &str) -> String {
+    format!(
+        r#"
+        const metadata = {{
+          name: '{name}',
+          uuid: '{uuid}',
+          baseUrl: 'http://localhost',
+          userAgent: 'test',
+          author: 'test',
+          version: '{version}',
+        }}
+        const search = ({{ key }}) => [{{ id: key, name: '{name}@{version}' }}]
+        "#
+    )
+}
+
+#[test]
+fn test_load_and_route() {
+    let mut registry = SourceRegistry::new();
+    let metadata = registry.load(source(UUID, "wenku8", "1.0.0")).unwrap();
+    assert_eq!(metadata.name, "wenku8");
+    let handle = registry.get(&UUID.to_uppercase()).unwrap();
+    let books = handle.search_books("1".to_string(), 1, 10).unwrap();
+    assert_eq!(books[0].name, "wenku8@1.0.0");
+    assert_eq!(registry.list().len(), 1);
+}
+
+#[test]
+fn test_reject_duplicate_and_conflict() {
+    let mut registry = SourceRegistry::new();
+    registry.load(source(UUID, "wenku8", "1.0.0")).unwrap();
+    let err = registry.load(source(UUID, "wenku8", "1.0.0")).unwrap_err();
+    assert!(matches!(err, BookCoreError::DuplicateSource(_)));
+    let err = registry.load(source(UUID, "other", "1.0.0")).unwrap_err();
+    assert!(matches!(err, BookCoreError::SourceConflict(_)));
+    let code = source(UUID, "other", "1.0.0");
+    let err = registry
+        .load_with(move || BookCore::init(code.clone()))
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::DuplicateSource(_)));
+    let err = registry
+        .load(source("not-a-uuid", "bad", "1.0.0"))
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::InvalidUuid(_)));
+}
+
+#[test]
+fn test_upgrade() {
+    let mut registry = SourceRegistry::new();
+    registry.load(source(UUID, "wenku8", "1.9.0")).unwrap();
+    let err = registry
+        .upgrade(source(UUID, "wenku8", "1.9.0"))
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::OutdatedVersion { .. }));
+    registry.upgrade(source(UUID, "wenku8", "1.10.0")).unwrap();
+    let books = registry
+        .get(UUID)
+        .unwrap()
+        .search_books("1".to_string(), 1, 10)
+        .unwrap();
+    assert_eq!(books[0].name, "wenku8@1.10.0");
+    let metadata = registry
+        .upgrade(source(UUID, "轻小说文库", "1.11.0"))
+        .unwrap();
+    assert_eq!(metadata.name, "轻小说文库");
+    assert_eq!(registry.list()[0].metadata.name, "轻小说文库");
+}
+
+#[test]
+fn test_upgrade_with_keeps_config() {
+    let build = |version: &str| {
+        let code = source(UUID, "wenku8", version) + "const spin = () => { while (true) {} }";
+        move || {
+            BookCore::builder()
+                .limits(ExecutionLimits::new().loop_iterations(1_000))
+                .build(code.clone())
+        }
+    };
+    let mut registry = SourceRegistry::new();
+    registry.load_with(build("1.0.0")).unwrap();
+    let err = registry.upgrade_with(build("1.0.0")).unwrap_err();
+    assert!(matches!(err, BookCoreError::OutdatedVersion { .. }));
+    let metadata = registry.upgrade_with(build("1.1.0")).unwrap();
+    assert_eq!(metadata.version, "1.1.0");
+    let err = registry
+        .get(UUID)
+        .unwrap()
+        .call(|core| core.invoke::<Value>("spin", Value::Null))
+        .unwrap_err();
+    assert!(matches!(err, BookCoreError::LimitExceeded(_)));
+}
+
+#[test]
+fn test_enable_disable_unload() {
+    let mut registry = SourceRegistry::new();
+    registry.load(source(UUID, "wenku8", "1.0.0")).unwrap();
+    registry.disable(UUID).unwrap();
+    assert!(matches!(
+        registry.get(UUID).unwrap_err(),
+        BookCoreError::SourceDisabled(_)
+    ));
+    assert!(!registry.list()[0].enabled);
+    registry.enable(UUID).unwrap();
+    assert!(registry.get(UUID).is_ok());
+    registry.unload(UUID).unwrap();
+    assert!(!registry.contains(UUID));
+    assert!(matches!(
+        registry.get(UUID).unwrap_err(),
+        BookCoreError::SourceNotFound(_)
+    ));
+}
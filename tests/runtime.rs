@@ -0,0 +1,75 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    thread,
+};
+
+use book_core::{BookCore, BookCoreHandle};
+
+const JS: &str = r#"
+const search = ({ key }) => {
+    const res = JReqwest.get(key, {})
+    return [{ id: String(res.status), name: res.body }]
+}
+"#;
+
+fn serve_once(body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 4096];
+        let mut request = Vec::new();
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = stream.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn test_sync_request() {
+    let url = serve_once("hello");
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let books = core.search_books(url, 1, 10).unwrap();
+    assert_eq!(books[0].id, "200");
+    assert_eq!(books[0].name, "hello");
+}
+
+#[test]
+fn test_async_on_current_thread_runtime() {
+    let url = serve_once("current");
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let handle = BookCoreHandle::spawn(JS.to_string()).unwrap();
+    let books = rt.block_on(handle.search_books_async(url, 1, 10)).unwrap();
+    assert_eq!(books[0].name, "current");
+}
+
+#[test]
+fn test_async_on_multi_thread_runtime() {
+    let url = serve_once("multi");
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let runtime = rt.handle().clone();
+    let handle = BookCoreHandle::spawn_with(move || {
+        BookCore::builder().runtime(runtime).build(JS.to_string())
+    })
+    .unwrap();
+    let books = rt.block_on(handle.search_books_async(url, 1, 10)).unwrap();
+    assert_eq!(books[0].name, "multi");
+}
@@ -0,0 +1,153 @@
+use book_core::{BookCore, BookCoreError, FilterValue, SearchFilterType, SearchQuery};
+use serde_json::json;
+
+const JS: &str = r#"
+const books = Array.from({ length: 45 }, (_, i) => ({ id: String(i), name: `book ${i}` }))
+const search = ({ key, page, count, cursor }) => {
+    if (key === 'legacy') {
+        return books.slice((page - 1) * count, page * count)
+    }
+    const start = cursor ? cursor.offset : 0
+    const items = books.slice(start, start + count)
+    const next = start + count
+    return {
+        items,
+        total: books.length,
+        hasMore: next < books.length,
+        nextCursor: next < books.length ? { offset: next } : null,
+    }
+}
+"#;
+
+#[test]
+fn test_cursor_pagination() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let mut query = SearchQuery::new("cursor").count(20);
+    let mut ids = Vec::new();
+    loop {
+        let page = core.search(query.clone()).unwrap();
+        assert_eq!(page.total, Some(45));
+        ids.extend(page.items.iter().map(|book| book.id.clone()));
+        if !page.has_more {
+            assert!(page.next_cursor.is_none());
+            break;
+        }
+        assert_eq!(page.next_cursor, Some(json!({ "offset": ids.len() })));
+        query = query.next(&page);
+    }
+    assert_eq!(ids.len(), 45);
+    assert_eq!(query.page, 3);
+}
+
+#[test]
+fn test_legacy_array() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let page = core
+        .search(SearchQuery::new("legacy").page(2).count(20))
+        .unwrap();
+    assert_eq!(page.items[0].id, "20");
+    assert!(page.has_more);
+    assert_eq!(page.total, None);
+    let page = core
+        .search(SearchQuery::new("legacy").page(3).count(20))
+        .unwrap();
+    assert_eq!(page.items.len(), 5);
+    assert!(!page.has_more);
+    let books = core.search_books("legacy".to_string(), 1, 10).unwrap();
+    assert_eq!(books.len(), 10);
+}
+
+#[test]
+fn test_large_page() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    let page = core
+        .search(SearchQuery::new("legacy").page(1).count(1000))
+        .unwrap();
+    assert_eq!(page.items.len(), 45);
+    assert!(!page.has_more);
+}
+
+const FILTER_JS: &str = r#"
+const searchFilters = [
+    {
+        id: 'type',
+        label: '搜索类型',
+        filterType: 'select',
+        options: [{ label: '书名', value: 'title' }, { label: '作者', value: 'author' }],
+        default: 'title',
+    },
+    {
+        id: 'tags',
+        label: '标签',
+        filterType: 'multiSelect',
+        options: [{ label: '科幻', value: 'scifi' }, { label: '日常', value: 'daily' }],
+    },
+    { id: 'words', label: '字数', filterType: 'range', min: 0, max: 5000000, unit: '字' },
+]
+const search = ({ key, filters }) => [{ id: key, name: JSON.stringify(filters ?? null) }]
+"#;
+
+#[test]
+fn test_filter_schema() {
+    let mut core = BookCore::init(FILTER_JS.to_string()).unwrap();
+    let filters = core.get_search_filters().unwrap();
+    assert_eq!(filters.len(), 3);
+    assert_eq!(filters[0].filter_type, SearchFilterType::Select);
+    assert_eq!(
+        filters[0].default,
+        Some(FilterValue::Single("title".to_string()))
+    );
+    assert_eq!(filters[2].unit.as_deref(), Some("字"));
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    assert!(core.get_search_filters().unwrap().is_empty());
+}
+
+#[test]
+fn test_search_with_filters() {
+    let mut core = BookCore::init(FILTER_JS.to_string()).unwrap();
+    let query = SearchQuery::new("k")
+        .filter("type", FilterValue::Single("author".to_string()))
+        .filter("tags", FilterValue::Multiple(vec!["scifi".to_string()]))
+        .filter(
+            "words",
+            FilterValue::Range {
+                min: Some(100000.0),
+                max: None,
+            },
+        );
+    let page = core.search(query).unwrap();
+    let filters: serde_json::Value = serde_json::from_str(&page.items[0].name).unwrap();
+    assert_eq!(
+        filters,
+        json!({
+            "type": "author",
+            "tags": ["scifi"],
+            "words": { "min": 100000, "max": null },
+        })
+    );
+    let page = core.search(SearchQuery::new("k")).unwrap();
+    assert_eq!(page.items[0].name, "null");
+}
+
+#[test]
+fn test_invalid_filters() {
+    let mut core = BookCore::init(FILTER_JS.to_string()).unwrap();
+    let invalid = [
+        ("type", FilterValue::Single("isbn".to_string())),
+        ("type", FilterValue::Multiple(vec!["title".to_string()])),
+        ("unknown", FilterValue::Single("x".to_string())),
+        (
+            "words",
+            FilterValue::Range {
+                min: Some(10.0),
+                max: Some(1.0),
+            },
+        ),
+    ];
+    for (id, value) in invalid {
+        let err = core
+            .search(SearchQuery::new("k").filter(id, value))
+            .unwrap_err();
+        assert!(matches!(err, BookCoreError::InvalidFilter { id: ref err_id, .. } if err_id == id));
+    }
+}
@@ -8,7 +8,7 @@ fn test_crypto_sha224() {
         console.log(text.toSha224())
     }
     "#;
-    let mut core = BookCore::init(js.to_string());
+    let mut core = BookCore::init(js.to_string()).unwrap();
     core.run_action("test".to_string()).unwrap();
 }
 
@@ -20,6 +20,6 @@ fn test_crypto_sha256() {
         console.log(text.toSha256("a90f3731745f1c30ee77cb13fc00005a"))
     }
     "#;
-    let mut core = BookCore::init(js.to_string());
+    let mut core = BookCore::init(js.to_string()).unwrap();
     core.run_action("test".to_string()).unwrap();
 }
@@ -0,0 +1,126 @@
+use book_core::{ActionResult, BookCore, BookCoreError, LoginRequired, ShelfResult};
+use serde_json::{json, Map, Value};
+
+const JS: &str = r#"
+const forms = [
+    {
+        id: 'login',
+        title: '用户登录',
+        fields: [
+            { fieldType: 'input', field: 'username', label: '用户名' },
+            { fieldType: 'button', field: 'login', label: '登录' },
+        ],
+    },
+]
+const login = ({ username }) => ({ envs: { token: `${username}-token` } })
+const books = ['1', '2', '3'].map((id) => ({
+    id,
+    name: `书${id}`,
+    latestChapter: { id: `${id}-1`, name: '第一章' },
+    readChapterId: id === '1' ? '1-1' : null,
+}))
+const requireLogin = () => getEnv('token') ? null : { type: 'loginRequired', form: 'login', message: '请先登录' }
+const shelf = ({ page, continuation }) => {
+    const login = requireLogin()
+    if (login) return login
+    const start = continuation ?? 0
+    return {
+        items: books.slice(start, start + 2),
+        continuation: start + 2 < books.length ? start + 2 : null,
+    }
+}
+const addToShelf = ({ bid }) => {
+    const login = requireLogin()
+    if (login) return login
+    if (books.some((book) => book.id === bid)) {
+        return { type: 'error', message: '已在书架中' }
+    }
+    books.push({ id: bid, name: `书${bid}` })
+    return { type: 'refreshShelf' }
+}
+const removeFromShelf = ({ bid }) => {
+    const index = books.findIndex((book) => book.id === bid)
+    books.splice(index, 1)
+    return null
+}
+"#;
+
+fn shelf_ids(core: &mut BookCore) -> Vec<String> {
+    match core.get_shelf().unwrap() {
+        ShelfResult::Books(books) => books.into_iter().map(|book| book.id).collect(),
+        result => panic!("expected books, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_login_required() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    assert!(core.has_shelf());
+    assert_eq!(
+        core.get_shelf().unwrap(),
+        ShelfResult::LoginRequired(LoginRequired {
+            form: Some("login".to_string()),
+            message: Some("请先登录".to_string()),
+        })
+    );
+    assert!(matches!(
+        core.add_to_shelf("4".to_string()).unwrap(),
+        ActionResult::LoginRequired(LoginRequired { form: Some(form), .. }) if form == "login"
+    ));
+    let values: Map<String, Value> = json!({ "username": "zsakvo" }).as_object().unwrap().clone();
+    core.submit_form("login", values).unwrap();
+    assert_eq!(shelf_ids(&mut core), ["1", "2", "3"]);
+}
+
+#[test]
+fn test_shelf_sync() {
+    let mut core = BookCore::init(JS.to_string()).unwrap();
+    core.set_env("token".to_string(), json!("token")).unwrap();
+    let ShelfResult::Books(books) = core.get_shelf().unwrap() else {
+        panic!("expected books");
+    };
+    assert_eq!(books[0].read_chapter_id.as_deref(), Some("1-1"));
+    assert_eq!(books[2].latest_chapter.as_ref().unwrap().id, "3-1");
+    assert_eq!(
+        core.add_to_shelf("4".to_string()).unwrap(),
+        ActionResult::RefreshShelf { message: None }
+    );
+    assert_eq!(
+        core.add_to_shelf("4".to_string()).unwrap(),
+        ActionResult::Error {
+            message: "已在书架中".to_string()
+        }
+    );
+    assert_eq!(
+        core.remove_from_shelf("2".to_string()).unwrap(),
+        ActionResult::Done
+    );
+    assert_eq!(shelf_ids(&mut core), ["1", "3", "4"]);
+}
+
+#[test]
+fn test_shelf_unsupported() {
+    let mut core = BookCore::init("const search = () => []".to_string()).unwrap();
+    assert!(!core.has_shelf());
+    let err = core.get_shelf().unwrap_err();
+    assert_eq!(err, BookCoreError::MissingEntry("shelf".to_string()));
+}
+
+#[test]
+fn test_too_many_pages() {
+    let js = "const shelf = ({ page }) => ({ items: [{ id: `${page}`, name: '书' }], continuation: page })";
+    let mut core = BookCore::builder()
+        .max_list_pages(4)
+        .build(js.to_string())
+        .unwrap();
+    let err = core.get_shelf().unwrap_err();
+    assert_eq!(err, BookCoreError::TooManyPages(4));
+}
+
+#[test]
+fn test_repeated_continuation() {
+    let js = "const shelf = ({ page }) => ({ items: [{ id: `${page}`, name: '书' }], continuation: page > 1 ? 'again' : 'next' })";
+    let mut core = BookCore::init(js.to_string()).unwrap();
+    let err = core.get_shelf().unwrap_err();
+    assert_eq!(err, BookCoreError::RepeatedContinuation(3));
+}
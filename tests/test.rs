@@ -3,7 +3,7 @@ use book_core::BookCore;
 #[test]
 fn search_books() {
     let code = include_str!("./wk8.js");
-    let mut wk8 = BookCore::init(code.to_string());
+    let mut wk8 = BookCore::init(code.to_string()).unwrap();
     let res = wk8.search_books("国王的求婚".to_string(), 1, 10).unwrap();
     println!("{:?}", res);
 }
@@ -11,7 +11,7 @@ fn search_books() {
 #[test]
 fn get_book_detail() {
     let code = include_str!("./wk8.js");
-    let mut wk8 = BookCore::init(code.to_string());
+    let mut wk8 = BookCore::init(code.to_string()).unwrap();
     let res = wk8.get_book_detail("3067".to_string()).unwrap();
     println!("{:?}", res);
 }
@@ -19,7 +19,7 @@ fn get_book_detail() {
 #[test]
 fn get_catalog() {
     let code = include_str!("./wk8.js");
-    let mut wk8 = BookCore::init(code.to_string());
+    let mut wk8 = BookCore::init(code.to_string()).unwrap();
     let res = wk8.get_catalog("3067".to_string()).unwrap();
     println!("{:?}", res);
 }
@@ -27,7 +27,7 @@ fn get_catalog() {
 #[test]
 fn get_chapter() {
     let code = include_str!("./wk8.js");
-    let mut wk8 = BookCore::init(code.to_string());
+    let mut wk8 = BookCore::init(code.to_string()).unwrap();
     let res = wk8
         .get_chapter("3067".to_string(), "126119".to_string())
         .unwrap();
@@ -37,7 +37,7 @@ fn get_chapter() {
 #[test]
 fn get_metadata() {
     let code = include_str!("./wk8.js");
-    let mut wk8 = BookCore::init(code.to_string());
+    let mut wk8 = BookCore::init(code.to_string()).unwrap();
     let res = wk8.get_metadata().unwrap();
     println!("{:?}", res);
 }
@@ -45,7 +45,7 @@ fn get_metadata() {
 #[test]
 fn get_forms() {
     let code = include_str!("./wk8.js");
-    let mut wk8 = BookCore::init(code.to_string());
+    let mut wk8 = BookCore::init(code.to_string()).unwrap();
     let res = wk8.get_forms().unwrap();
     println!("{:?}", res);
 }
@@ -53,7 +53,7 @@ fn get_forms() {
 #[test]
 fn action() {
     let code = include_str!("./wk8.js");
-    let mut wk8 = BookCore::init(code.to_string());
+    let mut wk8 = BookCore::init(code.to_string()).unwrap();
     let res = wk8.run_action("test".to_string()).unwrap();
     println!("{:?}", res);
 }
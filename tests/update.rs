@@ -0,0 +1,195 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+use book_core::{BookCoreError, SourceRegistry, UpdateStatus, UpdateTarget};
+
+const LATEST: &str = "352561f8-281c-4953-81f7-3772c6285c1c";
+const DETAIL: &str = "8d3c0f55-8b36-4a3e-9c1f-0c2d3a4b5c6d";
+const DISABLED: &str = "0b6c3a1e-5f0d-4c8e-9a7b-2d1e3f4a5b6c";
+const SLOW: &str = "5e2d7c1a-9b4f-4e3a-8c6d-1f0a2b3c4d5e";
+const POOLED: &str = "7a1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d";
+
+fn source(uuid: &str, entries: &str) -> String {
+    format!(
+        r#"
+        const metadata = {{
+          name: '{uuid}',
+          uuid: '{uuid}',
+          baseUrl: 'http://localhost',
+          userAgent: 'test',
+          author: 'test',
+          version: '1.0.0',
+        }}
+        {entries}
+        "#
+    )
+}
+
+fn registry() -> SourceRegistry {
+    let mut registry = SourceRegistry::new();
+    registry
+        .load(source(
+            LATEST,
+            r#"
+            const detail = () => { throw new Error('detail should not be called') }
+            const latest = ({ bid }) => bid === 'none' ? null : { id: `${bid}-10`, name: '第十章' }
+            "#,
+        ))
+        .unwrap();
+    registry
+        .load(source(
+            DETAIL,
+            r#"
+            const detail = ({ bid }) => {
+                if (bid === 'broken') throw new Error('boom')
+                return { id: bid, name: '书', latestChapter: { id: `${bid}-5`, name: '第五章' } }
+            }
+            "#,
+        ))
+        .unwrap();
+    registry.load(source(DISABLED, "")).unwrap();
+    registry.disable(DISABLED).unwrap();
+    registry
+}
+
+#[test]
+fn test_check_updates() {
+    let registry = registry();
+    let targets = vec![
+        UpdateTarget::new(LATEST, "a", Some("a-9".to_string())),
+        UpdateTarget::new(LATEST, "b", Some("b-10".to_string())),
+        UpdateTarget::new(LATEST, "none", None),
+        UpdateTarget::new(DETAIL.to_uppercase(), "c", Some("c-5".to_string())),
+        UpdateTarget::new(DETAIL, "d", None),
+        UpdateTarget::new(DETAIL, "broken", None),
+        UpdateTarget::new(DISABLED, "e", None),
+        UpdateTarget::new("not-a-uuid", "f", None),
+    ];
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut results = Vec::new();
+    rt.block_on(registry.check_updates(targets, 1, |check| results.push(check)));
+    assert_eq!(results.len(), 8);
+    let status = |bid: &str| {
+        &results
+            .iter()
+            .find(|check| check.target.bid == bid)
+            .unwrap()
+            .status
+    };
+    assert!(matches!(status("a"), UpdateStatus::Updated(latest) if latest.id == "a-10"));
+    assert_eq!(status("b"), &UpdateStatus::Unchanged);
+    assert_eq!(status("none"), &UpdateStatus::Unchanged);
+    assert_eq!(status("c"), &UpdateStatus::Unchanged);
+    assert!(matches!(status("d"), UpdateStatus::Updated(latest) if latest.name == "第五章"));
+    assert!(matches!(
+        status("broken"),
+        UpdateStatus::Failed(BookCoreError::Exception { .. })
+    ));
+    assert!(matches!(
+        status("e"),
+        UpdateStatus::Failed(BookCoreError::SourceDisabled(_))
+    ));
+    assert!(matches!(
+        status("f"),
+        UpdateStatus::Failed(BookCoreError::InvalidUuid(_))
+    ));
+}
+
+#[test]
+fn test_latest_chapter_from_handle() {
+    let registry = registry();
+    let handle = registry.get(LATEST).unwrap();
+    let latest = handle.get_latest_chapter("x".to_string()).unwrap().unwrap();
+    assert_eq!(latest.id, "x-10");
+    let handle = registry.get(DETAIL).unwrap();
+    let latest = handle.get_latest_chapter("y".to_string()).unwrap().unwrap();
+    assert_eq!(latest.id, "y-5");
+}
+
+#[test]
+fn test_in_flight_limit() {
+    let mut registry = SourceRegistry::new();
+    registry
+        .load(source(
+            SLOW,
+            r#"
+            const latest = ({ bid }) => {
+                const until = Date.now() + 50
+                while (Date.now() < until) {}
+                return { id: `${bid}-1`, name: '第一章' }
+            }
+            "#,
+        ))
+        .unwrap();
+    let handle = registry.get(SLOW).unwrap();
+    let targets = (0..10)
+        .map(|index| UpdateTarget::new(SLOW, index.to_string(), None))
+        .collect();
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let done = Cell::new(0);
+    let (_, done_before_probe) = rt.block_on(async {
+        futures_util::join!(
+            registry.check_updates(targets, 1, |_| done.set(done.get() + 1)),
+            async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                handle.get_metadata_async().await.unwrap();
+                done.get()
+            }
+        )
+    });
+    // 同一书源只排队一个检查，其他调用不必等全部检查完成
+    assert!(
+        done_before_probe <= 2,
+        "{} checks ran first",
+        done_before_probe
+    );
+    assert_eq!(done.get(), 10);
+}
+
+#[test]
+fn test_checks_run_in_parallel() {
+    let mut registry = SourceRegistry::new();
+    registry
+        .load(source(
+            POOLED,
+            r#"
+            const latest = ({ bid }) => {
+                const until = Date.now() + 200
+                while (Date.now() < until) {}
+                return { id: `${bid}-${getEnv('token')}`, name: '第一章' }
+            }
+            "#,
+        ))
+        .unwrap();
+    registry
+        .get(POOLED)
+        .unwrap()
+        .set_env("token".to_string(), "t".into())
+        .unwrap();
+    let targets = (0..8)
+        .map(|index| UpdateTarget::new(POOLED, index.to_string(), None))
+        .collect();
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut results = Vec::new();
+    let start = Instant::now();
+    rt.block_on(registry.check_updates(targets, 4, |check| results.push(check)));
+    let elapsed = start.elapsed();
+    // 依次执行需要 1.6 秒，四个工作线程并行约 0.4 秒
+    assert!(elapsed < Duration::from_millis(1200), "took {:?}", elapsed);
+    assert_eq!(results.len(), 8);
+    for check in results {
+        let id = format!("{}-t", check.target.bid);
+        assert!(matches!(check.status, UpdateStatus::Updated(latest) if latest.id == id));
+    }
+}